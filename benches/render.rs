@@ -0,0 +1,67 @@
+#![allow(clippy::needless_return)]
+
+use std::io::{self, Write};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rmod_gen::{EnumVariant, RustEnum, RustFile, RustMethod};
+
+const ENTRIES: usize = 25_000;
+
+/// A lookup table of roughly 50k lines: an enum with a variant per entry and a method matching
+/// on it.
+fn lookup_table() -> RustFile {
+    let mut lookup = RustEnum::new("Entry");
+    let mut body = String::from("return match self {\n");
+
+    for i in 0..ENTRIES {
+        lookup.push_variant(EnumVariant::new_empty(&format!("Entry{}", i)));
+        body.push_str(&format!("    Entry::Entry{} => {},\n", i, i));
+    }
+
+    body.push_str("};");
+
+    return RustFile::new()
+        .with_component(lookup.into())
+        .with_component(
+            RustMethod::new("value")
+                .with_argument("self")
+                .with_return_type("usize")
+                .with_body(&body)
+                .into(),
+        );
+}
+
+fn render(c: &mut Criterion) {
+    let file = lookup_table();
+    let mut group = c.benchmark_group("lookup_table");
+
+    group.bench_function("into_rust_code", |b| {
+        b.iter_batched(
+            || file.clone(),
+            |file| io::sink().write_all(file.into_rust_code().as_bytes()),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("write_to", |b| b.iter(|| file.write_to(io::sink())));
+
+    // Renders every component into a string of its own and joins those, to measure the cost of
+    // the intermediate strings against writing the whole file at once.
+    group.bench_function("join_component_strings", |b| {
+        b.iter(|| {
+            let code = file
+                .components()
+                .iter()
+                .map(|c| c.to_rust_string_with(0, file.render_options()))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            io::sink().write_all(code.as_bytes())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
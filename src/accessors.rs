@@ -0,0 +1,242 @@
+use crate::rust_component::Visibility;
+use crate::{RustImplementation, RustMethod, RustStruct};
+
+/// Types which are returned by value rather than by reference from generated getters.
+const COPY_TYPES: [&str; 17] = [
+    "bool", "char", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128",
+    "isize", "f32", "f64", "()",
+];
+
+/// Selects the accessor methods generated for a field. By default no accessors are generated.
+///
+/// ```
+/// use rmod_gen::Accessors;
+///
+/// let accessors = Accessors::none().with_getter().with_setter();
+/// ```
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Accessors {
+    getter: bool,
+    getter_mut: bool,
+    setter: bool,
+}
+
+/// Options for the accessors generated by [`RustStruct::generate_accessors`], selecting the
+/// accessors of each field by name.
+///
+/// ```
+/// use rmod_gen::{AccessorOptions, Accessors};
+///
+/// let options = AccessorOptions::new()
+///     .with_field("name", Accessors::none().with_getter())
+///     .with_field("age", Accessors::all());
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct AccessorOptions {
+    fields: Vec<(String, Accessors)>,
+}
+
+impl Accessors {
+    /// No accessors.
+    pub fn none() -> Self {
+        return Self {
+            getter: false,
+            getter_mut: false,
+            setter: false,
+        };
+    }
+
+    /// A getter, a mutable getter and a setter.
+    pub fn all() -> Self {
+        return Self {
+            getter: true,
+            getter_mut: true,
+            setter: true,
+        };
+    }
+
+    /// Adds a getter, `fn name(&self) -> &T`. Fields of primitive types and shared references
+    /// are returned by value instead, `fn name(&self) -> T`.
+    pub fn with_getter(mut self) -> Self {
+        self.getter = true;
+
+        return self;
+    }
+
+    /// Adds a mutable getter, `fn name_mut(&mut self) -> &mut T`.
+    pub fn with_getter_mut(mut self) -> Self {
+        self.getter_mut = true;
+
+        return self;
+    }
+
+    /// Adds a setter, `fn set_name(&mut self, name: T)`.
+    pub fn with_setter(mut self) -> Self {
+        self.setter = true;
+
+        return self;
+    }
+
+    pub fn getter(&self) -> bool {
+        return self.getter;
+    }
+
+    pub fn getter_mut(&self) -> bool {
+        return self.getter_mut;
+    }
+
+    pub fn setter(&self) -> bool {
+        return self.setter;
+    }
+}
+
+impl AccessorOptions {
+    /// Creates a new instance in which no accessors are generated.
+    pub fn new() -> Self {
+        return Self { fields: Vec::new() };
+    }
+
+    /// Selects the accessors generated for the field called `field`.
+    pub fn with_field(mut self, field: &str, accessors: Accessors) -> Self {
+        self.push_field(field, accessors);
+
+        return self;
+    }
+
+    /// Selects the accessors generated for the field called `field`.
+    pub fn push_field(&mut self, field: &str, accessors: Accessors) {
+        self.fields.push((field.to_string(), accessors));
+    }
+
+    fn field(&self, field: &str) -> Accessors {
+        return self
+            .fields
+            .iter()
+            .rev()
+            .find(|(name, _)| name == field)
+            .map_or(Accessors::none(), |(_, accessors)| *accessors);
+    }
+}
+
+impl RustStruct {
+    /// Generates an implementation containing the accessor methods selected for each field in
+    /// `options`. Each accessor is documented using the documentation of its field.
+    ///
+    /// * A getter, `fn name(&self) -> &T`, or `fn name(&self) -> T` for primitive types and shared
+    ///   references.
+    /// * A mutable getter, `fn name_mut(&mut self) -> &mut T`.
+    /// * A setter, `fn set_name(&mut self, name: T)`.
+    ///
+    /// ```
+    /// use rmod_gen::{AccessorOptions, Accessors, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Cow")
+    ///     .with_field(Field::private("age", "u64").with_docs("Age in years."))
+    ///     .with_field(Field::private("name", "String"));
+    ///
+    /// let options = AccessorOptions::new()
+    ///     .with_field("age", Accessors::none().with_getter())
+    ///     .with_field("name", Accessors::none().with_getter().with_setter());
+    ///
+    /// assert_eq!(
+    ///     s.generate_accessors(&options).to_rust_string(0),
+    ///     "impl Cow {\n    /// Age in years.\n    pub fn age(&self) -> u64 {\n        return self.age;\n    }\n\n    pub fn name(&self) -> &String {\n        return &self.name;\n    }\n\n    pub fn set_name(&mut self, name: String) {\n        self.name = name;\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn generate_accessors(&self, options: &AccessorOptions) -> RustImplementation {
+        let mut implementation = RustImplementation::for_struct(self);
+
+        for field in self.fields() {
+            let name = field.name();
+            let tp = field.field_type();
+            let accessors = options.field(name);
+
+            if accessors.getter() {
+                let getter = if is_copy_type(tp) {
+                    RustMethod::new(name)
+                        .with_return_type(tp)
+                        .with_body(&format!("return self.{};", name))
+                } else {
+                    RustMethod::new(name)
+                        .with_return_type(&format!("&{}", tp))
+                        .with_body(&format!("return &self.{};", name))
+                };
+
+                implementation.push_component(accessor(getter, field.docs(), "&self").into());
+            }
+
+            if accessors.getter_mut() {
+                let getter_mut = RustMethod::new(&format!("{}_mut", name))
+                    .with_return_type(&format!("&mut {}", tp))
+                    .with_body(&format!("return &mut self.{};", name));
+
+                implementation
+                    .push_component(accessor(getter_mut, field.docs(), "&mut self").into());
+            }
+
+            if accessors.setter() {
+                let setter = RustMethod::new(&format!("set_{}", name))
+                    .with_body(&format!("self.{} = {};", name, name));
+
+                implementation.push_component(
+                    accessor(setter, field.docs(), "&mut self")
+                        .with_argument(&format!("{}: {}", name, tp))
+                        .into(),
+                );
+            }
+        }
+
+        return implementation;
+    }
+}
+
+fn accessor(method: RustMethod, docs: &str, receiver: &str) -> RustMethod {
+    return method
+        .with_visibility(Visibility::Public)
+        .with_docs(docs)
+        .with_argument(receiver);
+}
+
+fn is_copy_type(tp: &str) -> bool {
+    let tp = tp.trim();
+
+    // Shared references are Copy, but mutable references, including `&'a mut T`, are not.
+    let is_shared_reference = match tp.strip_prefix('&') {
+        Some(referenced) => !referenced
+            .split_whitespace()
+            .take_while(|part| part.starts_with('\'') || *part == "mut")
+            .any(|part| part == "mut"),
+        None => false,
+    };
+
+    return COPY_TYPES.contains(&tp) || is_shared_reference;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::{Field, RustComponentTrait};
+
+    #[test]
+    fn test_is_copy_type() {
+        assert!(is_copy_type("u8"));
+        assert!(is_copy_type("&'a str"));
+        assert!(!is_copy_type("&'a mut str"));
+        assert!(!is_copy_type("String"));
+    }
+
+    #[test]
+    fn test_all_accessors_with_templates() {
+        let s = RustStruct::new("Holder")
+            .with_template("T: Clone")
+            .with_field(Field::private("value", "T").with_docs("The value."))
+            .with_field(Field::private("hidden", "u8"));
+        let options = AccessorOptions::new().with_field("value", Accessors::all());
+
+        assert_eq!(
+            s.generate_accessors(&options).to_rust_string(0),
+            "impl<T: Clone> Holder<T> {\n    /// The value.\n    pub fn value(&self) -> &T {\n        return &self.value;\n    }\n\n    /// The value.\n    pub fn value_mut(&mut self) -> &mut T {\n        return &mut self.value;\n    }\n\n    /// The value.\n    pub fn set_value(&mut self, value: T) {\n        self.value = value;\n    }\n}\n"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Renders declarative specs, or serialized component trees, to Rust files.
+//!
+//! Each input is written next to it with the extension `.rs` unless `--output`, `--out-dir` or
+//! `--stdout` is given. With `--check` nothing is written and the exit status is 1 if any output
+//! on disk differs from what would be generated, so that committed generated code can be checked
+//! in CI. Other errors exit with status 2.
+
+#![allow(clippy::needless_return)]
+
+use clap::{Parser, ValueEnum};
+use rmod_gen::{CodeFormatter, RustFile};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "rmod_gen",
+    version,
+    about = "Renders declarative specs to Rust files"
+)]
+struct Args {
+    /// The specs to render, in TOML, JSON or YAML as given by their extension.
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// The inputs are component trees serialized with the `serde` feature rather than specs.
+    #[arg(long)]
+    tree: bool,
+
+    /// The file to write, only allowed with a single input.
+    #[arg(short, long, conflicts_with_all = ["out_dir", "stdout"])]
+    output: Option<PathBuf>,
+
+    /// The directory to write `<input name>.rs` files to.
+    #[arg(long, conflicts_with = "stdout")]
+    out_dir: Option<PathBuf>,
+
+    /// Prints the generated code instead of writing it.
+    #[arg(long, conflicts_with = "check")]
+    stdout: bool,
+
+    /// Checks that the outputs are up to date instead of writing them.
+    #[arg(long)]
+    check: bool,
+
+    /// Wraps lines wider than this many columns.
+    #[arg(long)]
+    max_width: Option<usize>,
+
+    /// Writes a comment before each component of a tree with the place it was generated from, as
+    /// recorded in the tree. Specs don't record where their components are defined.
+    #[arg(long, requires = "tree")]
+    origin_comments: bool,
+
+    /// Formats the generated code.
+    #[arg(long, value_enum, default_value_t = Formatter::None)]
+    formatter: Formatter,
+
+    /// The edition passed to rustfmt.
+    #[arg(long, default_value = "2021")]
+    edition: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Formatter {
+    /// Keeps the code as generated.
+    None,
+    /// Runs the `rustfmt` found on the `PATH`.
+    Rustfmt,
+    /// Formats in process, only available when built with the `prettyplease` feature.
+    Prettyplease,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    return match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(error) => {
+            eprintln!("rmod_gen: {}", error);
+            ExitCode::from(2)
+        }
+    };
+}
+
+/// Renders every input, returning whether all the outputs were up to date in `--check` mode.
+fn run(args: &Args) -> Result<bool, String> {
+    if args.output.is_some() && args.inputs.len() > 1 {
+        return Err(String::from(
+            "--output can only be used with a single input",
+        ));
+    }
+
+    let formatter = formatter(args)?;
+    let mut up_to_date = true;
+
+    for input in &args.inputs {
+        let code = render(args, input, formatter.as_ref())?;
+
+        if args.stdout {
+            print!("{}", code);
+            continue;
+        }
+
+        let output = output_path(args, input);
+
+        if args.check {
+            if fs::read_to_string(&output).ok().as_deref() != Some(code.as_str()) {
+                eprintln!(
+                    "{} is out of date, regenerate it from {}",
+                    output.display(),
+                    input.display()
+                );
+                up_to_date = false;
+            }
+
+            continue;
+        }
+
+        fs::write(&output, code).map_err(|e| format!("{}: {}", output.display(), e))?;
+    }
+
+    return Ok(up_to_date);
+}
+
+fn render(args: &Args, input: &Path, formatter: Option<&CodeFormatter>) -> Result<String, String> {
+    let file = if args.tree {
+        RustFile::load_tree(input)
+    } else {
+        RustFile::load_spec(input)
+    }
+    .map_err(|e| e.to_string())?;
+
+    // Trees keep their own options unless they are overridden.
+    let mut options = *file.render_options();
+
+    if args.max_width.is_some() {
+        options.set_max_width(args.max_width);
+    }
+
+    if args.origin_comments {
+        options.set_origin_comments(true);
+    }
+
+    let code = file.with_render_options(options).into_rust_code();
+
+    return match formatter {
+        Some(formatter) => formatter
+            .format(&code)
+            .map_err(|e| format!("{}: {}", input.display(), e)),
+        None => Ok(code),
+    };
+}
+
+fn formatter(args: &Args) -> Result<Option<CodeFormatter>, String> {
+    return match args.formatter {
+        Formatter::None => Ok(None),
+        Formatter::Rustfmt => Ok(Some(CodeFormatter::rustfmt(&args.edition))),
+        #[cfg(feature = "prettyplease")]
+        Formatter::Prettyplease => Ok(Some(CodeFormatter::Prettyplease)),
+        #[cfg(not(feature = "prettyplease"))]
+        Formatter::Prettyplease => Err(String::from(
+            "prettyplease formatting requires the `prettyplease` feature",
+        )),
+    };
+}
+
+/// The file generated from `input`.
+fn output_path(args: &Args, input: &Path) -> PathBuf {
+    if let Some(output) = &args.output {
+        return output.clone();
+    }
+
+    let name = input.with_extension("rs");
+
+    return match &args.out_dir {
+        Some(dir) => dir.join(name.file_name().expect("inputs are files")),
+        None => name,
+    };
+}
@@ -0,0 +1,163 @@
+use crate::rust_component::Visibility;
+use crate::{RustMethod, RustStruct};
+
+/// Options for the constructor generated by [`RustStruct::constructor_with`].
+///
+/// ```
+/// use rmod_gen::ConstructorOptions;
+///
+/// let options = ConstructorOptions::new()
+///     .with_default_field("cache")
+///     .with_field_value("id", "next_id()")
+///     .with_into_arguments();
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct ConstructorOptions {
+    field_values: Vec<(String, String)>,
+    into_arguments: bool,
+}
+
+impl ConstructorOptions {
+    /// Creates a new instance in which every field is an argument of the constructor.
+    pub fn new() -> Self {
+        return Self {
+            field_values: Vec::new(),
+            into_arguments: false,
+        };
+    }
+
+    /// Excludes the field from the arguments, it is set to `Default::default()` instead.
+    pub fn with_default_field(self, field: &str) -> Self {
+        return self.with_field_value(field, "Default::default()");
+    }
+
+    /// Excludes the field from the arguments, it is set to the given expression instead.
+    pub fn with_field_value(mut self, field: &str, expression: &str) -> Self {
+        self.push_field_value(field, expression);
+
+        return self;
+    }
+
+    /// Arguments are accepted as `impl Into<T>` rather than `T`.
+    pub fn with_into_arguments(mut self) -> Self {
+        self.set_into_arguments(true);
+
+        return self;
+    }
+
+    /// Excludes the field from the arguments, it is set to the given expression instead.
+    pub fn push_field_value(&mut self, field: &str, expression: &str) {
+        self.field_values
+            .push((field.to_string(), expression.to_string()));
+    }
+
+    /// Sets whether arguments are accepted as `impl Into<T>` rather than `T`.
+    pub fn set_into_arguments(&mut self, into_arguments: bool) {
+        self.into_arguments = into_arguments;
+    }
+
+    fn field_value(&self, field: &str) -> Option<&str> {
+        return self
+            .field_values
+            .iter()
+            .rev()
+            .find(|(name, _)| name == field)
+            .map(|(_, expression)| expression.as_str());
+    }
+}
+
+impl RustStruct {
+    /// Generates a public `new` method which takes every field as an argument, in order, and
+    /// returns `Self`.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Point")
+    ///     .with_field(Field::private("x", "f64"))
+    ///     .with_field(Field::private("y", "f64"));
+    ///
+    /// assert_eq!(
+    ///     s.constructor().to_rust_string(0),
+    ///     "pub fn new(x: f64, y: f64) -> Self {\n    return Self {\n        x,\n        y,\n    };\n}\n"
+    /// );
+    /// ```
+    pub fn constructor(&self) -> RustMethod {
+        return self.constructor_with(&ConstructorOptions::new());
+    }
+
+    /// Generates a public `new` method like [`RustStruct::constructor`] using the given options.
+    ///
+    /// ```
+    /// use rmod_gen::{ConstructorOptions, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("User")
+    ///     .with_field(Field::private("name", "String"))
+    ///     .with_field(Field::private("friends", "Vec<String>"));
+    ///
+    /// let options = ConstructorOptions::new().with_default_field("friends").with_into_arguments();
+    ///
+    /// assert_eq!(
+    ///     s.constructor_with(&options).to_rust_string(0),
+    ///     "pub fn new(name: impl Into<String>) -> Self {\n    return Self {\n        name: name.into(),\n        friends: Default::default(),\n    };\n}\n"
+    /// );
+    /// ```
+    pub fn constructor_with(&self, options: &ConstructorOptions) -> RustMethod {
+        let mut method = RustMethod::new("new")
+            .with_visibility(Visibility::Public)
+            .with_return_type("Self");
+        let mut values = Vec::new();
+
+        for field in self.fields() {
+            let name = field.name();
+
+            if let Some(expression) = options.field_value(name) {
+                values.push(format!("{}: {},", name, expression));
+            } else if options.into_arguments {
+                method.push_argument(&format!("{}: impl Into<{}>", name, field.field_type()));
+                values.push(format!("{}: {}.into(),", name, name));
+            } else {
+                method.push_argument(&format!("{}: {}", name, field.field_type()));
+                values.push(format!("{},", name));
+            }
+        }
+
+        method.set_body(&crate::struct_builder::block(
+            "return Self {",
+            &values,
+            "};",
+        ));
+
+        return method;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::{Field, RustComponentTrait};
+
+    #[test]
+    fn test_empty_struct() {
+        assert_eq!(
+            RustStruct::new("Unit").constructor().to_rust_string(0),
+            "pub fn new() -> Self {\n    return Self {};\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_field_value() {
+        let s = RustStruct::new("Counter")
+            .with_field(Field::private("name", "&'static str"))
+            .with_field(Field::private("count", "u64"));
+
+        let options = ConstructorOptions::new().with_field_value("count", "1");
+
+        assert_eq!(
+            s.constructor_with(&options).to_rust_string(1),
+            "    pub fn new(name: &'static str) -> Self {\n        return Self {\n            name,\n            count: 1,\n        };\n    }\n"
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use crate::rust_component::{replace_identifier, RustComponent};
+use crate::{RustImplementation, RustMethod, RustStruct, RustText, RustTrait, TraitItem};
+
+use std::error::Error;
+use std::fmt;
+
+/// The reasons why methods cannot be forwarded to the inner field of a newtype.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DelegationError {
+    /// The struct does not have exactly one field to forward to.
+    NotNewtype { name: String, fields: usize },
+    /// The method does not take `self` in any form, so there is nothing to forward it to.
+    MissingReceiver { method: String },
+    /// The method takes `self` through a smart pointer such as `self: Box<Self>`, which cannot
+    /// be forwarded to a field.
+    UnsupportedReceiver { method: String, receiver: String },
+    /// An argument or the return type wraps `Self` in another type, e.g. `Option<Self>`, which
+    /// cannot be converted to or from the type of the field.
+    UnsupportedSelfType { method: String, tp: String },
+}
+
+impl RustImplementation {
+    /// Creates an inherent implementation for the newtype `newtype` in which every method in
+    /// `methods` forwards to the method of the same name on the single field of the struct. The
+    /// receiver and the argument names of each method are kept, arguments of type `Self`, `&Self`
+    /// or `&mut Self` are unwrapped and a return type of `Self` is wrapped again. Methods which
+    /// use `Self` in any other way, such as `self: Box<Self>` or `-> Option<Self>`, are rejected.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait, Visibility};
+    ///
+    /// let newtype = RustStruct::new("Names").with_field(Field::private("inner", "Vec<String>"));
+    ///
+    /// let implementation = RustImplementation::delegate_methods(
+    ///     &newtype,
+    ///     &[
+    ///         RustMethod::new("len").with_visibility(Visibility::Public).with_argument("&self").with_return_type("usize"),
+    ///         RustMethod::new("push").with_visibility(Visibility::Public).with_argument("&mut self").with_argument("value: String"),
+    ///     ],
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     implementation.to_rust_string(0),
+    ///     "impl Names {\n    pub fn len(&self) -> usize {\n        self.inner.len()\n    }\n\n    pub fn push(&mut self, value: String) {\n        self.inner.push(value)\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn delegate_methods(
+        newtype: &RustStruct,
+        methods: &[RustMethod],
+    ) -> Result<Self, DelegationError> {
+        let field = newtype_field(newtype)?;
+        let mut implementation = Self::for_struct(newtype);
+
+        for method in methods {
+            implementation.push_component(delegate_method(method, field)?.into());
+        }
+
+        return Ok(implementation);
+    }
+
+    /// Creates an implementation of `rust_trait` for the newtype `newtype` which forwards every
+    /// method of the trait, including provided methods, to the single field of the struct as
+    /// described in [`RustImplementation::delegate_methods`]. Associated types and constants are
+    /// taken from the implementation of the trait for the field's type.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod, RustStruct, RustTrait, TraitItem};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let rust_trait = RustTrait::new("Shape")
+    ///     .with_item(TraitItem::new_type("Unit", vec![], None))
+    ///     .with_item(TraitItem::new_required(RustMethod::new("area").with_argument("&self").with_return_type("f64")));
+    ///
+    /// let newtype = RustStruct::new("Tagged").with_template("S").with_field(Field::private("shape", "S"));
+    ///
+    /// assert_eq!(
+    ///     RustImplementation::delegate_trait(&newtype, &rust_trait).unwrap().to_rust_string(0),
+    ///     "impl<S> Shape for Tagged<S> where S: Shape {\n    type Unit = <S as Shape>::Unit;\n\n    fn area(&self) -> f64 {\n        self.shape.area()\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn delegate_trait(
+        newtype: &RustStruct,
+        rust_trait: &RustTrait,
+    ) -> Result<Self, DelegationError> {
+        let field = newtype_field(newtype)?;
+        let mut implementation = Self::trait_for_struct(rust_trait, newtype);
+
+        let template_names: Vec<&str> = rust_trait
+            .templates()
+            .iter()
+            .map(|t| crate::rust_component::template_name(t))
+            .collect();
+        let lifetimes: Vec<String> = rust_trait
+            .lifetimes()
+            .iter()
+            .map(|l| format!("'{}", l))
+            .collect();
+        let generics: Vec<&str> = lifetimes
+            .iter()
+            .map(String::as_str)
+            .chain(template_names)
+            .collect();
+
+        let trait_bound = if generics.is_empty() {
+            rust_trait.name().to_string()
+        } else {
+            format!("{}<{}>", rust_trait.name(), generics.join(", "))
+        };
+        let trait_path = format!("<{} as {}>", field.field_type(), trait_bound);
+
+        // The forwarded calls and items only exist if the field's type implements the trait.
+        implementation.push_where_predicate(&format!("{}: {}", field.field_type(), trait_bound));
+
+        for item in rust_trait.items() {
+            let component: RustComponent = match item {
+                TraitItem::RequiredMethod(method) | TraitItem::ProvidedMethod(method) => {
+                    delegate_method(method, field)?.into()
+                }
+                TraitItem::AssociatedType { name, .. } => {
+                    RustText::new(&format!("type {} = {}::{};", name, trait_path, name)).into()
+                }
+                TraitItem::AssociatedConst { name, tp, .. } => RustText::new(&format!(
+                    "const {}: {} = {}::{};",
+                    name, tp, trait_path, name
+                ))
+                .into(),
+                TraitItem::Other(_) => continue,
+            };
+
+            implementation.push_component(component);
+        }
+
+        return Ok(implementation);
+    }
+}
+
+impl fmt::Display for DelegationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            DelegationError::NotNewtype { name, fields } => write!(
+                f,
+                "cannot delegate to '{}', it has {} fields instead of exactly one",
+                name, fields
+            ),
+            DelegationError::MissingReceiver { method } => write!(
+                f,
+                "cannot delegate '{}', it does not take self as its first argument",
+                method
+            ),
+            DelegationError::UnsupportedReceiver { method, receiver } => write!(
+                f,
+                "cannot delegate '{}', its receiver '{}' cannot be forwarded to a field",
+                method, receiver
+            ),
+            DelegationError::UnsupportedSelfType { method, tp } => write!(
+                f,
+                "cannot delegate '{}', its type '{}' wraps Self",
+                method, tp
+            ),
+        };
+    }
+}
+
+impl Error for DelegationError {}
+
+fn newtype_field(newtype: &RustStruct) -> Result<&crate::rust_component::Field, DelegationError> {
+    return match newtype.fields().as_slice() {
+        [field] => Ok(field),
+        fields => Err(DelegationError::NotNewtype {
+            name: newtype.name().to_string(),
+            fields: fields.len(),
+        }),
+    };
+}
+
+fn is_receiver(argument: &str) -> bool {
+    let mut argument = argument.trim().trim_start_matches('&').trim_start();
+
+    if argument.starts_with('\'') {
+        argument = argument
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim_start());
+    }
+
+    let argument = argument.strip_prefix("mut ").unwrap_or(argument).trim();
+
+    return argument == "self" || argument.starts_with("self:") || argument.starts_with("self :");
+}
+
+/// Whether the explicit type of a receiver, if any, is `Self` or a plain reference to it.
+fn is_forwardable_receiver(receiver: &str) -> bool {
+    let Some((_, tp)) = receiver.split_once(':') else {
+        return true;
+    };
+    let mut tp = tp.trim().trim_start_matches('&').trim_start();
+
+    if tp.starts_with('\'') {
+        tp = tp
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim_start());
+    }
+
+    return tp.strip_prefix("mut ").unwrap_or(tp).trim() == "Self";
+}
+
+/// Whether `tp` mentions `Self` other than through an associated type such as `Self::Item`.
+fn mentions_self(tp: &str) -> bool {
+    return replace_identifier(tp, "Self", "") != tp;
+}
+
+/// Converts an argument such as `mut other: &Self` into the expression passed on to the field.
+fn forwarded_argument(argument: &str, field_name: &str) -> String {
+    let (pattern, tp) = argument.split_once(':').unwrap_or((argument, ""));
+    let pattern = pattern.trim();
+    let name = pattern.strip_prefix("mut ").unwrap_or(pattern).trim();
+
+    return match tp.trim() {
+        "Self" => format!("{}.{}", name, field_name),
+        "&Self" => format!("&{}.{}", name, field_name),
+        "&mut Self" => format!("&mut {}.{}", name, field_name),
+        _ => name.to_string(),
+    };
+}
+
+fn delegate_method(
+    method: &RustMethod,
+    field: &crate::rust_component::Field,
+) -> Result<RustMethod, DelegationError> {
+    let arguments = method.arguments();
+
+    if !arguments.first().is_some_and(|a| is_receiver(a)) {
+        return Err(DelegationError::MissingReceiver {
+            method: method.name().to_string(),
+        });
+    }
+
+    if !is_forwardable_receiver(&arguments[0]) {
+        return Err(DelegationError::UnsupportedReceiver {
+            method: method.name().to_string(),
+            receiver: arguments[0].trim().to_string(),
+        });
+    }
+
+    let argument_types = arguments[1..]
+        .iter()
+        .map(|a| a.split_once(':').map_or("", |(_, tp)| tp.trim()))
+        .filter(|tp| !matches!(*tp, "Self" | "&Self" | "&mut Self"));
+    let return_type = Some(method.return_type().trim()).filter(|tp| *tp != "Self");
+
+    if let Some(tp) = argument_types
+        .chain(return_type)
+        .find(|tp| mentions_self(tp))
+    {
+        return Err(DelegationError::UnsupportedSelfType {
+            method: method.name().to_string(),
+            tp: tp.to_string(),
+        });
+    }
+
+    let forwarded: Vec<String> = arguments[1..]
+        .iter()
+        .map(|a| forwarded_argument(a, field.name()))
+        .collect();
+
+    let call = format!(
+        "self.{}.{}({})",
+        field.name(),
+        method.name(),
+        forwarded.join(", ")
+    );
+
+    let body = if method.return_type().trim() == "Self" {
+        format!("Self {{ {}: {} }}", field.name(), call)
+    } else {
+        call
+    };
+
+    let mut method = method.clone();
+    method.set_with_body(true);
+    method.set_body(&body);
+
+    return Ok(method);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::{Field, RustComponentTrait};
+
+    #[test]
+    fn test_is_receiver() {
+        assert!(is_receiver("self"));
+        assert!(is_receiver("mut self"));
+        assert!(is_receiver("&self"));
+        assert!(is_receiver("&mut self"));
+        assert!(is_receiver("&'a mut self"));
+        assert!(is_receiver("self: Box<Self>"));
+        assert!(!is_receiver("selfish: u8"));
+        assert!(!is_receiver("value: u8"));
+    }
+
+    #[test]
+    fn test_self_arguments_and_return() {
+        let newtype = RustStruct::new("Meters").with_field(Field::private("value", "f64"));
+
+        let implementation = RustImplementation::delegate_methods(
+            &newtype,
+            &[RustMethod::new("max")
+                .with_argument("self")
+                .with_argument("other: Self")
+                .with_return_type("Self")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            implementation.to_rust_string(0),
+            "impl Meters {\n    fn max(self, other: Self) -> Self {\n        Self { value: self.value.max(other.value) }\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_trait_with_generics() {
+        let rust_trait = RustTrait::new("Combine")
+            .with_template("T")
+            .with_item(TraitItem::new_const("ID", "u8", Some("0")))
+            .with_item(TraitItem::new_provided(
+                RustMethod::new("combine")
+                    .with_argument("&mut self")
+                    .with_argument("other: &Self")
+                    .with_argument("mut extra: T")
+                    .with_body("unimplemented!()"),
+            ));
+
+        let newtype = RustStruct::new("Wrapper")
+            .with_lifetime("a")
+            .with_field(Field::private("inner", "Inner<'a>"));
+
+        assert_eq!(
+            RustImplementation::delegate_trait(&newtype, &rust_trait)
+                .unwrap()
+                .to_rust_string(0),
+            "impl<'a, T> Combine<T> for Wrapper<'a> where Inner<'a>: Combine<T> {\n    const ID: u8 = <Inner<'a> as Combine<T>>::ID;\n\n    fn combine(&mut self, other: &Self, mut extra: T) {\n        self.inner.combine(&other.inner, extra)\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let not_newtype = RustStruct::new("Pair")
+            .with_field(Field::private("a", "u8"))
+            .with_field(Field::private("b", "u8"));
+
+        assert_eq!(
+            RustImplementation::delegate_methods(&not_newtype, &[]),
+            Err(DelegationError::NotNewtype {
+                name: "Pair".to_string(),
+                fields: 2
+            })
+        );
+
+        let newtype = RustStruct::new("Id").with_field(Field::private("value", "u8"));
+
+        assert_eq!(
+            RustImplementation::delegate_methods(&newtype, &[RustMethod::new("new")]),
+            Err(DelegationError::MissingReceiver {
+                method: "new".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_unsupported_receivers() {
+        let newtype = RustStruct::new("Id").with_field(Field::private("value", "u8"));
+
+        for receiver in ["self: Box<Self>", "self: Rc<Self>", "self: Pin<&mut Self>"] {
+            assert_eq!(
+                RustImplementation::delegate_methods(
+                    &newtype,
+                    &[RustMethod::new("poll").with_argument(receiver)]
+                ),
+                Err(DelegationError::UnsupportedReceiver {
+                    method: "poll".to_string(),
+                    receiver: receiver.to_string()
+                })
+            );
+        }
+
+        assert!(RustImplementation::delegate_methods(
+            &newtype,
+            &[RustMethod::new("get").with_argument("self: &'a Self")]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_wrapped_self_types() {
+        let newtype = RustStruct::new("Id").with_field(Field::private("value", "u8"));
+
+        for return_type in ["Option<Self>", "Result<Self, Error>"] {
+            assert_eq!(
+                RustImplementation::delegate_methods(
+                    &newtype,
+                    &[RustMethod::new("checked")
+                        .with_argument("&self")
+                        .with_return_type(return_type)]
+                ),
+                Err(DelegationError::UnsupportedSelfType {
+                    method: "checked".to_string(),
+                    tp: return_type.to_string()
+                })
+            );
+        }
+
+        assert_eq!(
+            RustImplementation::delegate_methods(
+                &newtype,
+                &[RustMethod::new("extend")
+                    .with_argument("&mut self")
+                    .with_argument("others: Vec<Self>")]
+            ),
+            Err(DelegationError::UnsupportedSelfType {
+                method: "extend".to_string(),
+                tp: "Vec<Self>".to_string()
+            })
+        );
+
+        // Associated types of the trait are forwarded as well, so they need no conversion.
+        assert!(RustImplementation::delegate_methods(
+            &newtype,
+            &[RustMethod::new("next")
+                .with_argument("&mut self")
+                .with_return_type("Option<Self::Item>")]
+        )
+        .is_ok());
+    }
+}
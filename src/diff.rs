@@ -0,0 +1,498 @@
+use crate::rust_component::{Field, RenderContext, RustComponent, RustComponentTrait};
+use crate::{EnumVariant, RustFile, RustModule, TraitItem};
+
+use std::fmt;
+
+/// Whether an item was added, removed or changed.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The item only exists in the new tree.
+    Added,
+    /// The item only exists in the old tree.
+    Removed,
+    /// The item exists in both trees but its declaration differs, e.g. the type of a field or the
+    /// signature of a method. Changes to the children of an item, such as the fields of a struct,
+    /// are reported separately.
+    Changed,
+    /// The method exists in both trees with the same signature but its body differs.
+    BodyChanged,
+}
+
+/// An item of a component tree which can be compared: a component, a field of a struct, a variant
+/// of an enum or an item of a trait.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DiffItem<'a> {
+    Component(&'a RustComponent),
+    Field(&'a Field),
+    Variant(&'a EnumVariant),
+    TraitItem(&'a TraitItem),
+}
+
+/// A difference between two component trees.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<'a> {
+    kind: ChangeKind,
+    path: Vec<String>,
+    old: Option<DiffItem<'a>>,
+    new: Option<DiffItem<'a>>,
+    parents: Vec<DiffItem<'a>>,
+}
+
+impl RustFile {
+    /// The differences between this file and `new`. Items are matched by their kind and name, so a
+    /// renamed item is reported as removed and added, and items which compare equal are skipped.
+    ///
+    /// ```
+    /// use rmod_gen::{EnumVariant, RustEnum, RustFile, RustStruct};
+    /// use rmod_gen::rust_component::Field;
+    ///
+    /// let old = RustFile::new()
+    ///     .with_component(RustEnum::new("Opcode").with_variant(EnumVariant::new_empty("Nop")).into())
+    ///     .with_component(RustStruct::new("Header").with_field(Field::private("len", "u16")).into());
+    ///
+    /// let new = RustFile::new()
+    ///     .with_component(
+    ///         RustEnum::new("Opcode")
+    ///             .with_variant(EnumVariant::new_empty("Nop"))
+    ///             .with_variant(EnumVariant::new_empty("Halt"))
+    ///             .into(),
+    ///     )
+    ///     .with_component(RustStruct::new("Header").with_field(Field::private("len", "u32")).into());
+    ///
+    /// let changes: Vec<String> = old.diff(&new).iter().map(|c| c.to_string()).collect();
+    ///
+    /// assert_eq!(
+    ///     changes,
+    ///     vec![
+    ///         "enum `Opcode` gained variant `Halt`",
+    ///         "field `Header::len` changed from `len: u16` to `len: u32`",
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff<'a>(&'a self, new: &'a RustFile) -> Vec<Change<'a>> {
+        let mut changes = Vec::new();
+
+        diff_children(
+            &children_of_list(self.components()),
+            &children_of_list(new.components()),
+            &[],
+            &[],
+            &mut changes,
+        );
+
+        return changes;
+    }
+}
+
+impl RustModule {
+    /// The differences between the components of this module and `new`, as described by
+    /// [`RustFile::diff`]. The paths of the changes are relative to the modules, and changes to
+    /// the modules themselves, such as their visibility, are not included.
+    pub fn diff<'a>(&'a self, new: &'a RustModule) -> Vec<Change<'a>> {
+        let mut changes = Vec::new();
+
+        diff_children(
+            &children_of_list(self.components()),
+            &children_of_list(new.components()),
+            &[],
+            &[],
+            &mut changes,
+        );
+
+        return changes;
+    }
+}
+
+impl<'a> Change<'a> {
+    pub fn kind(&self) -> ChangeKind {
+        return self.kind;
+    }
+
+    /// The names of the item and the items containing it, e.g. `["api", "Header", "len"]`.
+    /// Implementations are named after what they implement, e.g. `Display for Header`.
+    pub fn path(&self) -> &[String] {
+        return &self.path;
+    }
+
+    /// The path separated by `::`, e.g. `api::Header::len`.
+    pub fn path_string(&self) -> String {
+        return self.path.join("::");
+    }
+
+    /// The item in the old tree, unless it was added.
+    pub fn old_item(&self) -> Option<DiffItem<'a>> {
+        return self.old;
+    }
+
+    /// The item in the new tree, unless it was removed.
+    pub fn new_item(&self) -> Option<DiffItem<'a>> {
+        return self.new;
+    }
+
+    /// The item in the new tree containing the item, e.g. the struct of a field. Items at the root
+    /// of the tree have no parent.
+    pub fn parent(&self) -> Option<DiffItem<'a>> {
+        return self.parents.last().copied();
+    }
+
+    /// The items in the new tree containing the item, starting at the root of the tree.
+    pub fn parents(&self) -> &[DiffItem<'a>] {
+        return &self.parents;
+    }
+
+    /// The item which was added, removed or changed, taken from the new tree where possible.
+    pub fn item(&self) -> DiffItem<'a> {
+        return self
+            .new
+            .or(self.old)
+            .expect("a change has an old or new item");
+    }
+}
+
+impl fmt::Display for Change<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let item = self.item();
+        let name = self.path.last().map(String::as_str).unwrap_or_default();
+
+        return match (self.kind, self.parent()) {
+            (ChangeKind::Added, Some(parent)) => write!(
+                f,
+                "{} `{}` gained {} `{}`",
+                parent.kind(),
+                self.path[..self.path.len() - 1].join("::"),
+                item.kind(),
+                name
+            ),
+            (ChangeKind::Removed, Some(parent)) => write!(
+                f,
+                "{} `{}` lost {} `{}`",
+                parent.kind(),
+                self.path[..self.path.len() - 1].join("::"),
+                item.kind(),
+                name
+            ),
+            (ChangeKind::Added, None) => write!(f, "added {} `{}`", item.kind(), name),
+            (ChangeKind::Removed, None) => write!(f, "removed {} `{}`", item.kind(), name),
+            (ChangeKind::Changed, _) => write!(
+                f,
+                "{} `{}` changed from `{}` to `{}`",
+                item.kind(),
+                self.path_string(),
+                self.old.map(|i| i.declaration()).unwrap_or_default(),
+                self.new.map(|i| i.declaration()).unwrap_or_default()
+            ),
+            (ChangeKind::BodyChanged, _) => {
+                write!(f, "{} `{}` body changed", item.kind(), self.path_string())
+            }
+        };
+    }
+}
+
+impl<'a> DiffItem<'a> {
+    /// The name of the item, e.g. the name of a field or method. Implementations are named after
+    /// what they implement and text is named after its first line.
+    pub fn name(&self) -> String {
+        return match self {
+            DiffItem::Component(RustComponent::Text(text)) => text.path_segment(),
+            DiffItem::Component(component) => component.name().unwrap_or_default().to_string(),
+            DiffItem::Field(field) => field.name().to_string(),
+            DiffItem::Variant(variant) => variant.name().to_string(),
+            DiffItem::TraitItem(item) => item.name().unwrap_or_default().to_string(),
+        };
+    }
+
+    /// The kind of the item as it is described in a change, e.g. `struct` or `field`.
+    pub fn kind(&self) -> &'static str {
+        return match self {
+            DiffItem::Component(component) => match component {
+                RustComponent::Module(_) => "module",
+                RustComponent::Struct(_) => "struct",
+                RustComponent::Enum(_) => "enum",
+                RustComponent::EnumVariant(_) => "variant",
+                RustComponent::Method(_) => "method",
+                RustComponent::Implementation(_) => "impl",
+                RustComponent::Variable(v) if v.is_const() => "const",
+                RustComponent::Variable(v) if v.is_static() => "static",
+                RustComponent::Variable(_) => "variable",
+                RustComponent::Text(_) => "text",
+                RustComponent::Trait(_) => "trait",
+            },
+            DiffItem::Field(_) => "field",
+            DiffItem::Variant(_) => "variant",
+            DiffItem::TraitItem(item) => match item {
+                TraitItem::RequiredMethod(_) | TraitItem::ProvidedMethod(_) => "method",
+                TraitItem::AssociatedType { .. } => "associated type",
+                TraitItem::AssociatedConst { .. } => "associated const",
+                TraitItem::Other(_) => "item",
+            },
+        };
+    }
+
+    /// The declaration of the item on a single line, without any of its children, e.g.
+    /// `pub struct Header { }` or `len: u16`. Methods are declared by their signature, without
+    /// their body.
+    pub fn declaration(&self) -> String {
+        let code = match self {
+            DiffItem::Component(component) => match component {
+                RustComponent::Method(o) => o.signature(),
+                RustComponent::Module(o) => {
+                    let mut o = o.clone();
+                    o.components_mut().clear();
+
+                    o.to_rust_string(0)
+                }
+                RustComponent::Struct(o) => {
+                    let mut o = o.clone();
+                    o.fields_mut().clear();
+
+                    o.to_rust_string(0)
+                }
+                RustComponent::Enum(o) => {
+                    let mut o = o.clone();
+                    o.variants_mut().clear();
+
+                    o.to_rust_string(0)
+                }
+                RustComponent::Implementation(o) => {
+                    let mut o = o.clone();
+                    o.components_mut().clear();
+
+                    o.to_rust_string(0)
+                }
+                RustComponent::Trait(o) => {
+                    let mut o = o.clone();
+                    o.items_mut().clear();
+
+                    o.to_rust_string(0)
+                }
+                component => component.to_rust_string(0),
+            },
+            DiffItem::Field(field) => field.to_string(),
+            DiffItem::Variant(variant) => variant.to_rust_string(0),
+            DiffItem::TraitItem(TraitItem::RequiredMethod(o) | TraitItem::ProvidedMethod(o)) => {
+                o.signature()
+            }
+            DiffItem::TraitItem(item) => {
+                let mut code = String::new();
+
+                item.write_rust(&mut code, &RenderContext::default())
+                    .expect("writing to a String does not fail");
+
+                code
+            }
+        };
+
+        let lines: Vec<&str> = code
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        return lines.join(" ").trim_end_matches(',').to_string();
+    }
+
+    /// The body of the item if it is a method with a body.
+    fn body(&self) -> Option<&'a str> {
+        return match self {
+            DiffItem::Component(RustComponent::Method(o))
+            | DiffItem::TraitItem(TraitItem::ProvidedMethod(o)) => Some(o.body()),
+            _ => None,
+        };
+    }
+
+    /// The key by which the item is matched in the other tree, its kind and name.
+    fn key(&self) -> (&'static str, String) {
+        return (self.kind(), self.name());
+    }
+
+    fn children(&self) -> Vec<DiffItem<'a>> {
+        return match self {
+            DiffItem::Component(component) => match component {
+                RustComponent::Module(o) => children_of_list(o.components()),
+                RustComponent::Implementation(o) => children_of_list(o.components()),
+                RustComponent::Struct(o) => o.fields().iter().map(DiffItem::Field).collect(),
+                RustComponent::Enum(o) => o.variants().iter().map(DiffItem::Variant).collect(),
+                RustComponent::Trait(o) => o.items().iter().map(DiffItem::TraitItem).collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+    }
+}
+
+fn children_of_list(components: &[RustComponent]) -> Vec<DiffItem<'_>> {
+    return components.iter().map(DiffItem::Component).collect();
+}
+
+/// Pairs the items of both lists by their keys and compares each pair.
+fn diff_children<'a>(
+    old: &[DiffItem<'a>],
+    new: &[DiffItem<'a>],
+    path: &[String],
+    parents: &[DiffItem<'a>],
+    changes: &mut Vec<Change<'a>>,
+) {
+    let mut matched = vec![false; new.len()];
+
+    for old_item in old {
+        let key = old_item.key();
+        let index = (0..new.len()).find(|&i| !matched[i] && new[i].key() == key);
+
+        match index {
+            Some(i) => {
+                matched[i] = true;
+                diff_items(*old_item, new[i], path, parents, changes);
+            }
+            None => changes.push(Change {
+                kind: ChangeKind::Removed,
+                path: child_path(path, old_item),
+                old: Some(*old_item),
+                new: None,
+                parents: parents.to_vec(),
+            }),
+        }
+    }
+
+    for (new_item, _) in new.iter().zip(matched).filter(|(_, matched)| !matched) {
+        changes.push(Change {
+            kind: ChangeKind::Added,
+            path: child_path(path, new_item),
+            old: None,
+            new: Some(*new_item),
+            parents: parents.to_vec(),
+        });
+    }
+}
+
+/// Compares an item in both trees, and then their children.
+fn diff_items<'a>(
+    old: DiffItem<'a>,
+    new: DiffItem<'a>,
+    path: &[String],
+    parents: &[DiffItem<'a>],
+    changes: &mut Vec<Change<'a>>,
+) {
+    if old == new {
+        return;
+    }
+
+    let path = child_path(path, &new);
+
+    let kind = if old.declaration() != new.declaration() {
+        Some(ChangeKind::Changed)
+    } else if old.body() != new.body() {
+        Some(ChangeKind::BodyChanged)
+    } else {
+        None
+    };
+
+    if let Some(kind) = kind {
+        changes.push(Change {
+            kind,
+            path: path.clone(),
+            old: Some(old),
+            new: Some(new),
+            parents: parents.to_vec(),
+        });
+    }
+
+    let mut parents = parents.to_vec();
+
+    parents.push(new);
+
+    diff_children(&old.children(), &new.children(), &path, &parents, changes);
+}
+
+fn child_path(path: &[String], item: &DiffItem) -> Vec<String> {
+    let mut path = path.to_vec();
+
+    path.push(item.name());
+
+    return path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Visibility;
+    use crate::{RustImplementation, RustMethod, RustStruct, RustText, RustTrait};
+
+    fn file(method: RustMethod, item: TraitItem) -> RustFile {
+        return RustFile::new().with_component(
+            RustModule::new("api")
+                .with_component(RustStruct::new("Foo").into())
+                .with_component(
+                    RustImplementation::new("Foo")
+                        .with_component(method.into())
+                        .into(),
+                )
+                .with_component(RustTrait::new("Bar").with_item(item).into())
+                .with_component(RustText::new("// Generated").into())
+                .into(),
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = file(
+            RustMethod::new("new"),
+            TraitItem::new_type("Output", Vec::new(), None),
+        );
+        let new = file(
+            RustMethod::new("new").with_argument("id: u64"),
+            TraitItem::new_const("ID", "u64", None),
+        )
+        .with_component(
+            RustStruct::new("Baz")
+                .with_visibility(Visibility::Public)
+                .into(),
+        );
+
+        let changes: Vec<String> = old.diff(&new).iter().map(|c| c.to_string()).collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                "method `api::Foo::new` changed from `fn new();` to `fn new(id: u64);`",
+                "trait `api::Bar` lost associated type `Output`",
+                "trait `api::Bar` gained associated const `ID`",
+                "added struct `Baz`",
+            ]
+        );
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_module() {
+        let old = RustModule::new("api").with_component(RustStruct::new("Foo").into());
+        let new = RustModule::new("api");
+
+        let changes = old.diff(&new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind(), ChangeKind::Removed);
+        assert_eq!(changes[0].path(), &["Foo"]);
+    }
+
+    #[test]
+    fn test_diff_method_body() {
+        let old = RustModule::new("api").with_component(
+            RustMethod::new("len")
+                .with_return_type("u16")
+                .with_body("0")
+                .into(),
+        );
+        let new = RustModule::new("api").with_component(
+            RustMethod::new("len")
+                .with_return_type("u16")
+                .with_body("1")
+                .into(),
+        );
+
+        let changes = old.diff(&new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind(), ChangeKind::BodyChanged);
+        assert_eq!(changes[0].to_string(), "method `len` body changed");
+    }
+}
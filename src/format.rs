@@ -0,0 +1,248 @@
+use crate::RustFile;
+
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// A formatter used to tidy up generated code.
+///
+/// ```
+/// use rmod_gen::{CodeFormatter, RustFile, RustMethod};
+///
+/// let formatted = RustFile::new()
+///     .with_component(RustMethod::new("main").with_body("let   x=1;").into())
+///     .into_formatted_code(&CodeFormatter::rustfmt("2021"));
+///
+/// if formatted.diagnostic().is_none() {
+///     assert_eq!(formatted.code(), "fn main() {\n    let x = 1;\n}\n");
+/// }
+/// ```
+///
+/// The available formatters depend on the enabled features, so matching on a formatter needs a
+/// wildcard arm.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodeFormatter {
+    /// Formats the code in process using `prettyplease`. Comments other than doc comments are
+    /// removed. Only available with the `prettyplease` feature.
+    #[cfg(feature = "prettyplease")]
+    Prettyplease,
+    /// Formats the code by running the `rustfmt` executable `binary` with the given edition.
+    Rustfmt { binary: String, edition: String },
+}
+
+/// The reasons why code could not be formatted.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum FormatError {
+    /// The code is not valid Rust.
+    Parse { message: String },
+    /// The formatter could not be run or communicated with.
+    Io { message: String },
+    /// The formatter exited unsuccessfully, `status` is `None` if it was terminated by a signal.
+    Rustfmt { status: Option<i32>, stderr: String },
+}
+
+/// The result of formatting generated code. If formatting failed the unformatted code is kept
+/// and the reason is available from [`FormattedCode::diagnostic`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FormattedCode {
+    code: String,
+    diagnostic: Option<FormatError>,
+}
+
+impl CodeFormatter {
+    /// Creates a formatter which runs the `rustfmt` found on the `PATH` with the given edition,
+    /// e.g. `"2021"`.
+    pub fn rustfmt(edition: &str) -> Self {
+        return CodeFormatter::Rustfmt {
+            binary: String::from("rustfmt"),
+            edition: edition.to_string(),
+        };
+    }
+
+    /// Formats some code, returning an error rather than falling back to the original code.
+    pub fn format(&self, code: &str) -> Result<String, FormatError> {
+        return match self {
+            #[cfg(feature = "prettyplease")]
+            CodeFormatter::Prettyplease => {
+                let file = syn::parse_file(code).map_err(|e| FormatError::Parse {
+                    message: e.to_string(),
+                })?;
+
+                Ok(prettyplease::unparse(&file))
+            }
+            CodeFormatter::Rustfmt { binary, edition } => run_rustfmt(binary, edition, code),
+        };
+    }
+
+    /// Formats some code, falling back to the original code if it can't be formatted.
+    pub fn format_or_original(&self, code: String) -> FormattedCode {
+        return match self.format(&code) {
+            Ok(code) => FormattedCode {
+                code,
+                diagnostic: None,
+            },
+            Err(error) => FormattedCode {
+                code,
+                diagnostic: Some(error),
+            },
+        };
+    }
+}
+
+impl FormattedCode {
+    /// The formatted code, or the original code if formatting failed.
+    pub fn code(&self) -> &str {
+        return &self.code;
+    }
+
+    /// The reason formatting failed, if it did.
+    pub fn diagnostic(&self) -> Option<&FormatError> {
+        return self.diagnostic.as_ref();
+    }
+
+    /// Converts into the code, discarding any diagnostic.
+    pub fn into_code(self) -> String {
+        return self.code;
+    }
+}
+
+impl RustFile {
+    /// Converts the file into Rust code, as in [`RustFile::into_rust_code`], and formats it with
+    /// `formatter`. If formatting fails the unformatted code is returned along with a diagnostic.
+    pub fn into_formatted_code(self, formatter: &CodeFormatter) -> FormattedCode {
+        return formatter.format_or_original(self.into_rust_code());
+    }
+}
+
+fn run_rustfmt(binary: &str, edition: &str, code: &str) -> Result<String, FormatError> {
+    let mut child = Command::new(binary)
+        .arg("--edition")
+        .arg(edition)
+        .arg("--emit")
+        .arg("stdout")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(io_error)?;
+
+    let mut stdin = child.stdin.take().ok_or_else(|| FormatError::Io {
+        message: String::from("could not open the standard input of rustfmt"),
+    })?;
+
+    // Written from another thread so that a full stdout pipe can't block writing the input.
+    let input = code.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child.wait_with_output().map_err(io_error)?;
+
+    writer
+        .join()
+        .map_err(|_| FormatError::Io {
+            message: String::from("writing to rustfmt panicked"),
+        })?
+        .map_err(io_error)?;
+
+    if !output.status.success() {
+        return Err(FormatError::Rustfmt {
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+
+    return String::from_utf8(output.stdout).map_err(|e| FormatError::Io {
+        message: e.to_string(),
+    });
+}
+
+fn io_error(error: std::io::Error) -> FormatError {
+    return FormatError::Io {
+        message: error.to_string(),
+    };
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            FormatError::Parse { message } => write!(f, "could not parse the code: {}", message),
+            FormatError::Io { message } => write!(f, "could not run the formatter: {}", message),
+            FormatError::Rustfmt {
+                status: Some(status),
+                stderr,
+            } => write!(
+                f,
+                "rustfmt exited with status {}: {}",
+                status,
+                stderr.trim()
+            ),
+            FormatError::Rustfmt {
+                status: None,
+                stderr,
+            } => write!(f, "rustfmt was terminated: {}", stderr.trim()),
+        };
+    }
+}
+
+impl Error for FormatError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RustMethod;
+
+    fn file() -> RustFile {
+        return RustFile::new().with_component(
+            RustMethod::new("main")
+                .with_argument("a: u8")
+                .with_body("let   x=a;")
+                .into(),
+        );
+    }
+
+    #[test]
+    fn test_missing_rustfmt_falls_back() {
+        let formatter = CodeFormatter::Rustfmt {
+            binary: String::from("rmod_gen_missing_rustfmt"),
+            edition: String::from("2021"),
+        };
+
+        let formatted = file().into_formatted_code(&formatter);
+
+        assert_eq!(formatted.code(), file().into_rust_code());
+        assert!(matches!(
+            formatted.diagnostic(),
+            Some(FormatError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rustfmt_invalid_code() {
+        let formatted = CodeFormatter::rustfmt("2021").format_or_original(String::from("fn a({"));
+
+        assert_eq!(formatted.code(), "fn a({");
+        assert!(formatted.diagnostic().is_some());
+    }
+
+    #[cfg(feature = "prettyplease")]
+    #[test]
+    fn test_prettyplease() {
+        let formatted = file().into_formatted_code(&CodeFormatter::Prettyplease);
+
+        assert_eq!(formatted.diagnostic(), None);
+        assert_eq!(formatted.code(), "fn main(a: u8) {\n    let x = a;\n}\n");
+    }
+
+    #[cfg(feature = "prettyplease")]
+    #[test]
+    fn test_prettyplease_invalid_code() {
+        let formatted = CodeFormatter::Prettyplease.format_or_original(String::from("fn a({"));
+
+        assert_eq!(formatted.code(), "fn a({");
+        assert!(matches!(
+            formatted.diagnostic(),
+            Some(FormatError::Parse { .. })
+        ));
+    }
+}
@@ -1,3 +1,20 @@
+// The explicit `return` style and `Into` implementations are used throughout the crate.
+#![allow(
+    clippy::needless_return,
+    clippy::from_over_into,
+    clippy::new_without_default
+)]
+
+mod accessors;
+mod constructor;
+mod delegation;
+mod diff;
+mod format;
+mod manual_impls;
+mod merge;
+#[cfg(feature = "parse")]
+mod parse;
+mod query;
 pub mod rust_component;
 mod rust_enum;
 mod rust_file;
@@ -8,7 +25,28 @@ mod rust_struct;
 mod rust_text;
 mod rust_trait;
 mod rust_variable;
+mod semver;
+mod source_map;
+#[cfg(feature = "spec")]
+pub mod spec;
+mod struct_builder;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tokens")]
+mod tokens;
+pub mod visit;
+mod wrap;
 
+pub use accessors::{AccessorOptions, Accessors};
+pub use constructor::ConstructorOptions;
+pub use delegation::DelegationError;
+pub use diff::{Change, ChangeKind, DiffItem};
+pub use format::{CodeFormatter, FormatError, FormattedCode};
+pub use manual_impls::{FieldHandling, ManualImplOptions};
+pub use merge::{MergeConflict, MergeError};
+#[cfg(feature = "parse")]
+pub use parse::ParseError;
+pub use query::PathError;
 pub use rust_enum::{EnumVariant, EnumVariantBuilder, RustEnum};
 pub use rust_file::RustFile;
 pub use rust_impl::RustImplementation;
@@ -16,11 +54,143 @@ pub use rust_method::RustMethod;
 pub use rust_module::RustModule;
 pub use rust_struct::RustStruct;
 pub use rust_text::RustText;
-pub use rust_trait::RustTrait;
+pub use rust_trait::{RustTrait, TraitItem};
 pub use rust_variable::RustVariable;
+pub use semver::{Compatibility, SemverChange, SemverReport, VersionBump};
+pub use source_map::{SourceMap, SourceMapEntry};
+#[cfg(feature = "spec")]
+pub use spec::{SpecError, SpecFormat};
+pub use struct_builder::BuilderOptions;
+
+use rust_component::{RenderContext, RustComponent};
+use std::fmt;
 
 const TAB_SIZE: usize = 4;
 
+#[cfg(feature = "indent_tabs")]
+const INDENT: &str = "\t";
+
+#[cfg(not(feature = "indent_tabs"))]
+const INDENT: &str = "    ";
+
+/// Writes the indentation for `indent_level`.
+fn write_indent(w: &mut dyn fmt::Write, indent_level: usize) -> fmt::Result {
+    for _ in 0..indent_level {
+        w.write_str(INDENT)?;
+    }
+
+    return Ok(());
+}
+
+/// Writes some text with every line indented to `indent_level`. Empty lines and lines within a
+/// multi-line string literal are not indented, the new lines of the text are kept as they are.
+fn write_indented(w: &mut dyn fmt::Write, text: &str, indent_level: usize) -> fmt::Result {
+    let continuations = wrap::string_continuations(text);
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            w.write_char('\n')?;
+        }
+
+        let in_string = i > 0 && continuations[i - 1];
+
+        if !line.is_empty() && !in_string {
+            write_indent(w, indent_level)?;
+        }
+
+        w.write_str(line)?;
+    }
+
+    return Ok(());
+}
+
+/// Writes the components of a module or implementation, each on its own lines. Components are
+/// separated by an empty line, unless they are both single line components such as variables.
+fn write_components(
+    w: &mut dyn fmt::Write,
+    components: &[RustComponent],
+    ctx: &RenderContext,
+) -> fmt::Result {
+    for (i, component) in components.iter().enumerate() {
+        if i > 0 && !(component.is_single_line() && components[i - 1].is_single_line()) {
+            w.write_char('\n')?;
+        }
+
+        component.write_rust(&mut TrimTrailingNewlines::new(w), ctx)?;
+        w.write_char('\n')?;
+    }
+
+    return Ok(());
+}
+
+/// Writes some text as doc comment lines, e.g. `///` or `//!`, indented to the desired level.
+/// Each line, including the last, ends in a new line.
+fn write_docs(
+    w: &mut dyn fmt::Write,
+    docs: &str,
+    marker: &str,
+    indent_level: usize,
+) -> fmt::Result {
+    for line in docs.lines() {
+        write_indent(w, indent_level)?;
+        w.write_str(marker)?;
+
+        if !line.is_empty() {
+            w.write_char(' ')?;
+            w.write_str(line)?;
+        }
+
+        w.write_char('\n')?;
+    }
+
+    return Ok(());
+}
+
+/// Writes to another writer, dropping the new lines at the very end of the output.
+struct TrimTrailingNewlines<'a> {
+    inner: &'a mut dyn fmt::Write,
+    pending_newlines: usize,
+}
+
+impl<'a> TrimTrailingNewlines<'a> {
+    fn new(inner: &'a mut dyn fmt::Write) -> Self {
+        return Self {
+            inner,
+            pending_newlines: 0,
+        };
+    }
+}
+
+impl fmt::Write for TrimTrailingNewlines<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let trimmed = s.trim_end_matches('\n');
+
+        if !trimmed.is_empty() {
+            for _ in 0..self.pending_newlines {
+                self.inner.write_char('\n')?;
+            }
+
+            self.pending_newlines = 0;
+            self.inner.write_str(trimmed)?;
+        }
+
+        self.pending_newlines += s.len() - trimmed.len();
+
+        return Ok(());
+    }
+}
+
+/// The width of a line indented to `indent_level`, counting tabs as `TAB_SIZE` columns.
+fn line_width(indent_level: usize, line: &str) -> usize {
+    let indent_width = TAB_SIZE * indent_level;
+
+    return indent_width
+        + line
+            .chars()
+            .map(|c| if c == '\t' { TAB_SIZE } else { 1 })
+            .sum::<usize>();
+}
+
 #[cfg(feature = "indent_tabs")]
 fn indent_string(indent_level: usize) -> String {
     return "\t".repeat(indent_level);
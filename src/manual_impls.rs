@@ -0,0 +1,787 @@
+use crate::rust_component::template_name;
+use crate::{EnumVariant, RustEnum, RustImplementation, RustMethod, RustStruct};
+
+/// How a field is treated by a manually generated trait implementation, see [`ManualImplOptions`].
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub enum FieldHandling {
+    /// The field is left out. `Debug` omits the field, `PartialEq` and `Hash` ignore it and
+    /// `Clone` and `Default` use `Default::default()` for it.
+    Skip,
+    /// The expression is used in place of the field. Within the expression `{self}` and `{other}`
+    /// are replaced with the field of `self` and `other` respectively.
+    ///
+    /// * `Debug` formats the value of the expression.
+    /// * `Clone` and `Default` use the value of the expression for the field.
+    /// * `PartialEq` uses the expression, which must be a `bool`, to compare the field.
+    /// * `Hash` hashes the value of the expression.
+    Override(String),
+}
+
+/// Per field options for the trait implementations generated by methods such as
+/// [`RustStruct::impl_debug`] and [`RustEnum::impl_partial_eq`]. Fields are identified by their
+/// name, or by their position for enum value variants, e.g. `0`. For enums the field can be
+/// limited to a single variant with `Variant::field`.
+///
+/// ```
+/// use rmod_gen::ManualImplOptions;
+///
+/// let options = ManualImplOptions::new()
+///     .with_skipped_field("callback")
+///     .with_field_override("raw", "std::ptr::eq({self}, {other})");
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct ManualImplOptions {
+    fields: Vec<(String, FieldHandling)>,
+}
+
+/// A field being generated along with the expressions that refer to it.
+struct FieldAccess<'a> {
+    name: String,
+    /// The field of `self` as a reference, e.g. `&self.name` or a binding in a match.
+    self_ref: String,
+    /// The field of `self` as it is compared or cloned, e.g. `self.name` or a binding in a match.
+    self_value: String,
+    /// The field of `other` as it is compared.
+    other_value: String,
+    /// The field of `self` as a place expression, substituted for `{self}`.
+    self_place: String,
+    /// The field of `other` as a place expression, substituted for `{other}`.
+    other_place: String,
+    handling: Option<&'a FieldHandling>,
+}
+
+impl ManualImplOptions {
+    /// Creates a new instance where every field is handled as it would be by a derive.
+    pub fn new() -> Self {
+        return Self { fields: Vec::new() };
+    }
+
+    /// Leaves the field out of the implementation, see [`FieldHandling::Skip`].
+    pub fn with_skipped_field(mut self, field: &str) -> Self {
+        self.push_field_handling(field, FieldHandling::Skip);
+
+        return self;
+    }
+
+    /// Uses the expression in place of the field, see [`FieldHandling::Override`].
+    pub fn with_field_override(mut self, field: &str, expression: &str) -> Self {
+        self.push_field_handling(field, FieldHandling::Override(expression.to_string()));
+
+        return self;
+    }
+
+    /// Sets how the field is handled.
+    pub fn push_field_handling(&mut self, field: &str, handling: FieldHandling) {
+        self.fields.push((field.to_string(), handling));
+    }
+
+    fn handling(&self, variant: Option<&str>, field: &str) -> Option<&FieldHandling> {
+        let qualified = variant.map(|v| format!("{}::{}", v, field));
+
+        return self
+            .fields
+            .iter()
+            .rev()
+            .find(|(name, _)| Some(name) == qualified.as_ref())
+            .or_else(|| self.fields.iter().rev().find(|(name, _)| name == field))
+            .map(|(_, handling)| handling);
+    }
+}
+
+impl RustStruct {
+    /// Generates an implementation of `std::fmt::Debug` equivalent to `#[derive(Debug)]`, with
+    /// skipped fields omitted using `finish_non_exhaustive`.
+    ///
+    /// ```
+    /// use rmod_gen::{ManualImplOptions, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Task")
+    ///     .with_field(Field::private("name", "String"))
+    ///     .with_field(Field::private("run", "fn()"));
+    ///
+    /// assert_eq!(
+    ///     s.impl_debug(&ManualImplOptions::new().with_skipped_field("run")).to_rust_string(0),
+    ///     "impl std::fmt::Debug for Task {\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n        return f\n            .debug_struct(\"Task\")\n            .field(\"name\", &self.name)\n            .finish_non_exhaustive();\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn impl_debug(&self, options: &ManualImplOptions) -> RustImplementation {
+        let indent_string = crate::indent_string(1);
+        let mut lines = vec![
+            "return f".to_string(),
+            format!("{}.debug_struct(\"{}\")", indent_string, self.name()),
+        ];
+        let mut finish = ".finish();";
+
+        for field in self.field_accesses(options) {
+            match field.debug_value() {
+                Some(value) => lines.push(format!(
+                    "{}.field(\"{}\", {})",
+                    indent_string, field.name, value
+                )),
+                None => finish = ".finish_non_exhaustive();",
+            }
+        }
+
+        lines.push(format!("{}{}", indent_string, finish));
+
+        return self
+            .manual_impl("std::fmt::Debug")
+            .with_component(debug_method("f", &lines.join("\n")).into());
+    }
+
+    /// Generates an implementation of `Clone` equivalent to `#[derive(Clone)]`.
+    pub fn impl_clone(&self, options: &ManualImplOptions) -> RustImplementation {
+        let values: Vec<String> = self
+            .field_accesses(options)
+            .iter()
+            .map(|field| format!("{}: {},", field.name, field.clone_value()))
+            .collect();
+
+        return self.manual_impl("Clone").with_component(
+            clone_method(&crate::struct_builder::block(
+                "return Self {",
+                &values,
+                "};",
+            ))
+            .into(),
+        );
+    }
+
+    /// Generates an implementation of `PartialEq` equivalent to `#[derive(PartialEq)]`.
+    ///
+    /// ```
+    /// use rmod_gen::{ManualImplOptions, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Node")
+    ///     .with_field(Field::private("id", "u64"))
+    ///     .with_field(Field::private("parent", "*const Node"));
+    ///
+    /// let options = ManualImplOptions::new().with_field_override("parent", "std::ptr::eq({self}, {other})");
+    ///
+    /// assert_eq!(
+    ///     s.impl_partial_eq(&options).to_rust_string(0),
+    ///     "impl PartialEq for Node {\n    fn eq(&self, other: &Self) -> bool {\n        return self.id == other.id && std::ptr::eq(self.parent, other.parent);\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn impl_partial_eq(&self, options: &ManualImplOptions) -> RustImplementation {
+        let comparisons: Vec<String> = self
+            .field_accesses(options)
+            .iter()
+            .filter_map(FieldAccess::comparison)
+            .collect();
+
+        let method = if comparisons.is_empty() {
+            eq_method("_other", "return true;")
+        } else {
+            eq_method("other", &format!("return {};", comparisons.join(" && ")))
+        };
+
+        return self.manual_impl("PartialEq").with_component(method.into());
+    }
+
+    /// Generates an implementation of `std::hash::Hash` equivalent to `#[derive(Hash)]`.
+    pub fn impl_hash(&self, options: &ManualImplOptions) -> RustImplementation {
+        let statements: Vec<String> = self
+            .field_accesses(options)
+            .iter()
+            .filter_map(FieldAccess::hash_statement)
+            .collect();
+
+        let method = if statements.is_empty() {
+            hash_method("_state", "")
+        } else {
+            hash_method("state", &statements.join("\n"))
+        };
+
+        return self
+            .manual_impl("std::hash::Hash")
+            .with_component(method.into());
+    }
+
+    /// Generates an implementation of `Default` equivalent to `#[derive(Default)]`, overridden
+    /// fields are set to their expression.
+    pub fn impl_default(&self, options: &ManualImplOptions) -> RustImplementation {
+        let values: Vec<String> = self
+            .field_accesses(options)
+            .iter()
+            .map(|field| format!("{}: {},", field.name, field.default_value()))
+            .collect();
+
+        return self.manual_impl("Default").with_component(
+            default_method(&crate::struct_builder::block(
+                "return Self {",
+                &values,
+                "};",
+            ))
+            .into(),
+        );
+    }
+
+    fn manual_impl(&self, trait_path: &str) -> RustImplementation {
+        return trait_impl(trait_path, self.name(), self.templates(), self.lifetimes());
+    }
+
+    fn field_accesses<'a>(&'a self, options: &'a ManualImplOptions) -> Vec<FieldAccess<'a>> {
+        return self
+            .fields()
+            .iter()
+            .map(|field| FieldAccess {
+                name: field.name().to_string(),
+                self_ref: format!("&self.{}", field.name()),
+                self_value: format!("self.{}", field.name()),
+                other_value: format!("other.{}", field.name()),
+                self_place: format!("self.{}", field.name()),
+                other_place: format!("other.{}", field.name()),
+                handling: options.handling(None, field.name()),
+            })
+            .collect();
+    }
+}
+
+impl RustEnum {
+    /// Generates an implementation of `std::fmt::Debug` equivalent to `#[derive(Debug)]`, see
+    /// [`RustStruct::impl_debug`].
+    ///
+    /// ```
+    /// use rmod_gen::{EnumVariant, ManualImplOptions, RustEnum};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let e = RustEnum::new("Event")
+    ///     .with_variant(EnumVariant::build("Quit").build())
+    ///     .with_variant(EnumVariant::build("Click").with_value("u32").with_value("u32").build());
+    ///
+    /// assert_eq!(
+    ///     e.impl_debug(&ManualImplOptions::new()).to_rust_string(0),
+    ///     "impl std::fmt::Debug for Event {\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n        return match self {\n            Self::Quit => f.write_str(\"Quit\"),\n            Self::Click(self_0, self_1) => f.debug_tuple(\"Click\").field(self_0).field(self_1).finish(),\n        };\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn impl_debug(&self, options: &ManualImplOptions) -> RustImplementation {
+        let arms = self.match_arms(|variant| {
+            let fields = variant_accesses(variant, options);
+            let values: Vec<Option<String>> = fields.iter().map(FieldAccess::debug_value).collect();
+            let pattern = variant_pattern(variant, "self", |i| {
+                values[i].is_some() && fields[i].uses_self()
+            });
+
+            let expression = match variant {
+                EnumVariant::EmptyVariant { name } => format!("f.write_str(\"{}\")", name),
+                EnumVariant::ValueVariant { name, .. } => {
+                    let mut expression = format!("f.debug_tuple(\"{}\")", name);
+
+                    for value in values.iter().flatten() {
+                        expression.push_str(&format!(".field({})", value));
+                    }
+
+                    expression + ".finish()"
+                }
+                EnumVariant::StructVariant { name, .. } => {
+                    let mut expression = format!("f.debug_struct(\"{}\")", name);
+
+                    for (field, value) in fields.iter().zip(&values) {
+                        if let Some(value) = value {
+                            expression.push_str(&format!(".field(\"{}\", {})", field.name, value));
+                        }
+                    }
+
+                    if values.iter().all(Option::is_some) {
+                        expression + ".finish()"
+                    } else {
+                        expression + ".finish_non_exhaustive()"
+                    }
+                }
+            };
+
+            return format!("{} => {},", pattern, expression);
+        });
+
+        return self.manual_impl("std::fmt::Debug").with_component(
+            debug_method(&self.argument_name("f"), &self.match_self(&arms)).into(),
+        );
+    }
+
+    /// Generates an implementation of `Clone` equivalent to `#[derive(Clone)]`, see
+    /// [`RustStruct::impl_clone`].
+    pub fn impl_clone(&self, options: &ManualImplOptions) -> RustImplementation {
+        let arms = self.match_arms(|variant| {
+            let fields = variant_accesses(variant, options);
+            let pattern = variant_pattern(variant, "self", |i| fields[i].uses_self());
+            let values: Vec<String> = fields.iter().map(FieldAccess::clone_value).collect();
+
+            return format!("{} => {},", pattern, variant_expression(variant, &values));
+        });
+
+        return self
+            .manual_impl("Clone")
+            .with_component(clone_method(&self.match_self(&arms)).into());
+    }
+
+    /// Generates an implementation of `PartialEq` equivalent to `#[derive(PartialEq)]`, see
+    /// [`RustStruct::impl_partial_eq`].
+    pub fn impl_partial_eq(&self, options: &ManualImplOptions) -> RustImplementation {
+        let mut arms = self.match_arms(|variant| {
+            let fields = variant_accesses(variant, options);
+            let comparisons: Vec<Option<String>> =
+                fields.iter().map(FieldAccess::comparison).collect();
+            let self_pattern = variant_pattern(variant, "self", |i| {
+                comparisons[i].is_some() && fields[i].uses_self()
+            });
+            let other_pattern = variant_pattern(variant, "other", |i| {
+                comparisons[i].is_some() && fields[i].uses_other()
+            });
+            let comparisons: Vec<String> = comparisons.into_iter().flatten().collect();
+
+            let comparison = if comparisons.is_empty() {
+                "true".to_string()
+            } else {
+                comparisons.join(" && ")
+            };
+
+            return format!("({}, {}) => {},", self_pattern, other_pattern, comparison);
+        });
+
+        if self.variants().len() > 1 {
+            arms.push("_ => false,".to_string());
+        }
+
+        let method = if self.variants().is_empty() {
+            eq_method("_other", &self.match_self(&arms))
+        } else {
+            eq_method(
+                "other",
+                &match_block("return match (self, other) {", &arms, "};"),
+            )
+        };
+
+        return self.manual_impl("PartialEq").with_component(method.into());
+    }
+
+    /// Generates an implementation of `std::hash::Hash` equivalent to `#[derive(Hash)]`, see
+    /// [`RustStruct::impl_hash`].
+    pub fn impl_hash(&self, options: &ManualImplOptions) -> RustImplementation {
+        let indent_string = crate::indent_string(1);
+
+        let arms = self.match_arms(|variant| {
+            let fields = variant_accesses(variant, options);
+            let statements: Vec<Option<String>> =
+                fields.iter().map(FieldAccess::hash_statement).collect();
+            let pattern = variant_pattern(variant, "self", |i| {
+                statements[i].is_some() && fields[i].uses_self()
+            });
+            let statements: Vec<String> = statements
+                .into_iter()
+                .flatten()
+                .map(|s| format!("{}{}", indent_string, s))
+                .collect();
+
+            if statements.is_empty() {
+                return format!("{} => {{}}", pattern);
+            }
+
+            return format!("{} => {{\n{}\n}}", pattern, statements.join("\n"));
+        });
+
+        let body = if self.variants().is_empty() {
+            self.match_self(&arms)
+        } else {
+            format!(
+                "std::hash::Hash::hash(&std::mem::discriminant(self), state);\n\n{}",
+                match_block("match self {", &arms, "}")
+            )
+        };
+
+        return self
+            .manual_impl("std::hash::Hash")
+            .with_component(hash_method(&self.argument_name("state"), &body).into());
+    }
+
+    /// Generates an implementation of `Default` which returns `variant`, with each of its fields
+    /// set to `Default::default()` or their overridden expression. Returns `None` if the enum has
+    /// no variant named `variant`.
+    ///
+    /// ```
+    /// use rmod_gen::{EnumVariant, ManualImplOptions, RustEnum};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let e = RustEnum::new("Level")
+    ///     .with_variant(EnumVariant::build("Off").build())
+    ///     .with_variant(EnumVariant::build("On").with_field("brightness", "u8").build());
+    ///
+    /// let options = ManualImplOptions::new().with_field_override("brightness", "100");
+    ///
+    /// assert_eq!(
+    ///     e.impl_default("On", &options).unwrap().to_rust_string(0),
+    ///     "impl Default for Level {\n    fn default() -> Self {\n        return Self::On { brightness: 100 };\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn impl_default(
+        &self,
+        variant: &str,
+        options: &ManualImplOptions,
+    ) -> Option<RustImplementation> {
+        let variant = self.variants().iter().find(|v| v.name() == variant)?;
+
+        let values: Vec<String> = variant_accesses(variant, options)
+            .iter()
+            .map(FieldAccess::default_value)
+            .collect();
+
+        return Some(self.manual_impl("Default").with_component(
+            default_method(&format!("return {};", variant_expression(variant, &values))).into(),
+        ));
+    }
+
+    fn manual_impl(&self, trait_path: &str) -> RustImplementation {
+        return trait_impl(trait_path, self.name(), self.templates(), self.lifetimes());
+    }
+
+    /// A match on `self` returning the result of the arms. Enums without any variants are matched
+    /// by value since a reference to an uninhabited type is not considered empty.
+    fn match_self(&self, arms: &[String]) -> String {
+        if self.variants().is_empty() {
+            return "match *self {}".to_string();
+        }
+
+        return match_block("return match self {", arms, "};");
+    }
+
+    /// The name of an argument, prefixed with an underscore if the enum has no variants and so
+    /// the argument is never used.
+    fn argument_name(&self, argument: &str) -> String {
+        if self.variants().is_empty() {
+            return format!("_{}", argument);
+        }
+
+        return argument.to_string();
+    }
+
+    fn match_arms<F: Fn(&EnumVariant) -> String>(&self, arm: F) -> Vec<String> {
+        return self.variants().iter().map(arm).collect();
+    }
+}
+
+impl FieldAccess<'_> {
+    fn resolve(&self, expression: &str) -> String {
+        return expression
+            .replace("{self}", &self.self_place)
+            .replace("{other}", &self.other_place);
+    }
+
+    fn uses_self(&self) -> bool {
+        return match self.handling {
+            Some(FieldHandling::Skip) => false,
+            Some(FieldHandling::Override(expression)) => expression.contains("{self}"),
+            None => true,
+        };
+    }
+
+    fn uses_other(&self) -> bool {
+        return match self.handling {
+            Some(FieldHandling::Skip) => false,
+            Some(FieldHandling::Override(expression)) => expression.contains("{other}"),
+            None => true,
+        };
+    }
+
+    fn debug_value(&self) -> Option<String> {
+        return match self.handling {
+            Some(FieldHandling::Skip) => None,
+            Some(FieldHandling::Override(expression)) => {
+                Some(format!("&({})", self.resolve(expression)))
+            }
+            None => Some(self.self_ref.clone()),
+        };
+    }
+
+    fn clone_value(&self) -> String {
+        return match self.handling {
+            Some(FieldHandling::Skip) => "Default::default()".to_string(),
+            Some(FieldHandling::Override(expression)) => self.resolve(expression),
+            None => format!("{}.clone()", self.self_value),
+        };
+    }
+
+    fn comparison(&self) -> Option<String> {
+        return match self.handling {
+            Some(FieldHandling::Skip) => None,
+            Some(FieldHandling::Override(expression)) => Some(self.resolve(expression)),
+            None => Some(format!("{} == {}", self.self_value, self.other_value)),
+        };
+    }
+
+    fn hash_statement(&self) -> Option<String> {
+        return match self.handling {
+            Some(FieldHandling::Skip) => None,
+            Some(FieldHandling::Override(expression)) => Some(format!(
+                "std::hash::Hash::hash(&({}), state);",
+                self.resolve(expression)
+            )),
+            None => Some(format!("std::hash::Hash::hash({}, state);", self.self_ref)),
+        };
+    }
+
+    fn default_value(&self) -> String {
+        return match self.handling {
+            Some(FieldHandling::Override(expression)) => expression.clone(),
+            Some(FieldHandling::Skip) | None => "Default::default()".to_string(),
+        };
+    }
+}
+
+/// Creates an implementation of a trait for a type, bounding each template by the trait.
+fn trait_impl(
+    trait_path: &str,
+    name: &str,
+    templates: &[String],
+    lifetimes: &[String],
+) -> RustImplementation {
+    let mut implementation = RustImplementation::new_for(trait_path, name);
+
+    for lifetime in lifetimes {
+        implementation.push_lifetime(lifetime);
+    }
+
+    for template in templates {
+        if template.contains(':') {
+            implementation.push_impl_template(&format!("{} + {}", template, trait_path));
+        } else {
+            implementation.push_impl_template(&format!("{}: {}", template, trait_path));
+        }
+
+        implementation.push_target_template(template_name(template));
+    }
+
+    return implementation;
+}
+
+fn variant_accesses<'a>(
+    variant: &'a EnumVariant,
+    options: &'a ManualImplOptions,
+) -> Vec<FieldAccess<'a>> {
+    let names: Vec<String> = match variant {
+        EnumVariant::StructVariant { fields, .. } => {
+            fields.iter().map(|f| f.name().to_string()).collect()
+        }
+        EnumVariant::ValueVariant { types, .. } => {
+            (0..types.len()).map(|i| i.to_string()).collect()
+        }
+        EnumVariant::EmptyVariant { .. } => Vec::new(),
+    };
+
+    return names
+        .into_iter()
+        .map(|name| FieldAccess {
+            self_ref: format!("self_{}", name),
+            self_value: format!("self_{}", name),
+            other_value: format!("other_{}", name),
+            self_place: format!("(*self_{})", name),
+            other_place: format!("(*other_{})", name),
+            handling: options.handling(Some(variant.name()), &name),
+            name,
+        })
+        .collect();
+}
+
+/// The pattern matching a variant where each used field is bound to `prefix_field`.
+fn variant_pattern<F: Fn(usize) -> bool>(variant: &EnumVariant, prefix: &str, used: F) -> String {
+    return match variant {
+        EnumVariant::EmptyVariant { name } => format!("Self::{}", name),
+        EnumVariant::ValueVariant { name, types } => {
+            let bindings: Vec<String> = (0..types.len())
+                .map(|i| {
+                    if used(i) {
+                        format!("{}_{}", prefix, i)
+                    } else {
+                        "_".to_string()
+                    }
+                })
+                .collect();
+
+            format!("Self::{}({})", name, bindings.join(", "))
+        }
+        EnumVariant::StructVariant { name, fields } => {
+            let mut bindings: Vec<String> = fields
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| used(*i))
+                .map(|(_, f)| format!("{}: {}_{}", f.name(), prefix, f.name()))
+                .collect();
+
+            if bindings.len() != fields.len() {
+                bindings.push("..".to_string());
+            }
+
+            if bindings.is_empty() {
+                format!("Self::{} {{}}", name)
+            } else {
+                format!("Self::{} {{ {} }}", name, bindings.join(", "))
+            }
+        }
+    };
+}
+
+/// The expression constructing a variant from the value of each of its fields.
+fn variant_expression(variant: &EnumVariant, values: &[String]) -> String {
+    return match variant {
+        EnumVariant::EmptyVariant { name } => format!("Self::{}", name),
+        EnumVariant::ValueVariant { name, .. } => format!("Self::{}({})", name, values.join(", ")),
+        EnumVariant::StructVariant { name, fields } => {
+            let values: Vec<String> = fields
+                .iter()
+                .zip(values)
+                .map(|(f, v)| format!("{}: {}", f.name(), v))
+                .collect();
+
+            if values.is_empty() {
+                format!("Self::{} {{}}", name)
+            } else {
+                format!("Self::{} {{ {} }}", name, values.join(", "))
+            }
+        }
+    };
+}
+
+/// A match expression, arms may span several lines.
+fn match_block(opening: &str, arms: &[String], closing: &str) -> String {
+    let lines: Vec<String> = arms
+        .iter()
+        .flat_map(|arm| arm.lines().map(str::to_string).collect::<Vec<String>>())
+        .collect();
+
+    return crate::struct_builder::block(opening, &lines, closing);
+}
+
+fn debug_method(f: &str, body: &str) -> RustMethod {
+    return RustMethod::new("fmt")
+        .with_argument("&self")
+        .with_argument(&format!("{}: &mut std::fmt::Formatter<'_>", f))
+        .with_return_type("std::fmt::Result")
+        .with_body(body);
+}
+
+fn clone_method(body: &str) -> RustMethod {
+    return RustMethod::new("clone")
+        .with_argument("&self")
+        .with_return_type("Self")
+        .with_body(body);
+}
+
+fn eq_method(other: &str, body: &str) -> RustMethod {
+    return RustMethod::new("eq")
+        .with_argument("&self")
+        .with_argument(&format!("{}: &Self", other))
+        .with_return_type("bool")
+        .with_body(body);
+}
+
+fn hash_method(state: &str, body: &str) -> RustMethod {
+    return RustMethod::new("hash")
+        .with_template("H: std::hash::Hasher")
+        .with_argument("&self")
+        .with_argument(&format!("{}: &mut H", state))
+        .with_body(body);
+}
+
+fn default_method(body: &str) -> RustMethod {
+    return RustMethod::new("default")
+        .with_return_type("Self")
+        .with_body(body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::{Field, RustComponentTrait};
+
+    #[test]
+    fn test_struct_clone_and_hash_with_templates() {
+        let s = RustStruct::new("Entry")
+            .with_template("K")
+            .with_template("V: Copy")
+            .with_field(Field::private("key", "K"))
+            .with_field(Field::private("value", "V"))
+            .with_field(Field::private("callback", "fn()"));
+
+        let options = ManualImplOptions::new().with_skipped_field("callback");
+
+        assert_eq!(
+            s.impl_clone(&ManualImplOptions::new()).to_rust_string(0),
+            "impl<K: Clone, V: Copy + Clone> Clone for Entry<K, V> {\n    fn clone(&self) -> Self {\n        return Self {\n            key: self.key.clone(),\n            value: self.value.clone(),\n            callback: self.callback.clone(),\n        };\n    }\n}\n"
+        );
+        assert_eq!(
+            s.impl_hash(&options).to_rust_string(0),
+            "impl<K: std::hash::Hash, V: Copy + std::hash::Hash> std::hash::Hash for Entry<K, V> {\n    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {\n        std::hash::Hash::hash(&self.key, state);\n        std::hash::Hash::hash(&self.value, state);\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_struct() {
+        let s = RustStruct::new("Unit");
+
+        assert_eq!(
+            s.impl_partial_eq(&ManualImplOptions::new()).to_rust_string(0),
+            "impl PartialEq for Unit {\n    fn eq(&self, _other: &Self) -> bool {\n        return true;\n    }\n}\n"
+        );
+        assert_eq!(
+            s.impl_default(&ManualImplOptions::new()).to_rust_string(0),
+            "impl Default for Unit {\n    fn default() -> Self {\n        return Self {};\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_enum_partial_eq() {
+        let e = RustEnum::new("Shape")
+            .with_variant(EnumVariant::build("Circle").with_value("f64").build())
+            .with_variant(
+                EnumVariant::build("Callback")
+                    .with_field("id", "u8")
+                    .with_field("run", "fn()")
+                    .build(),
+            );
+
+        let options = ManualImplOptions::new()
+            .with_field_override("Callback::run", "{self} as usize == {other} as usize");
+
+        assert_eq!(
+            e.impl_partial_eq(&options).to_rust_string(0),
+            "impl PartialEq for Shape {\n    fn eq(&self, other: &Self) -> bool {\n        return match (self, other) {\n            (Self::Circle(self_0), Self::Circle(other_0)) => self_0 == other_0,\n            (Self::Callback { id: self_id, run: self_run }, Self::Callback { id: other_id, run: other_run }) => self_id == other_id && (*self_run) as usize == (*other_run) as usize,\n            _ => false,\n        };\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_enum_hash_and_clone_skipped() {
+        let e = RustEnum::new("Message")
+            .with_variant(EnumVariant::build("Empty").build())
+            .with_variant(
+                EnumVariant::build("Data")
+                    .with_value("u8")
+                    .with_value("fn()")
+                    .build(),
+            );
+
+        let options = ManualImplOptions::new().with_skipped_field("1");
+
+        assert_eq!(
+            e.impl_hash(&options).to_rust_string(0),
+            "impl std::hash::Hash for Message {\n    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {\n        std::hash::Hash::hash(&std::mem::discriminant(self), state);\n\n        match self {\n            Self::Empty => {}\n            Self::Data(self_0, _) => {\n                std::hash::Hash::hash(self_0, state);\n            }\n        }\n    }\n}\n"
+        );
+        assert_eq!(
+            e.impl_clone(&options).to_rust_string(0),
+            "impl Clone for Message {\n    fn clone(&self) -> Self {\n        return match self {\n            Self::Empty => Self::Empty,\n            Self::Data(self_0, _) => Self::Data(self_0.clone(), Default::default()),\n        };\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_enum_without_variants() {
+        let e = RustEnum::new("Never");
+
+        assert_eq!(
+            e.impl_debug(&ManualImplOptions::new()).to_rust_string(0),
+            "impl std::fmt::Debug for Never {\n    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n        match *self {}\n    }\n}\n"
+        );
+        assert!(e
+            .impl_default("Missing", &ManualImplOptions::new())
+            .is_none());
+    }
+}
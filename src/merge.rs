@@ -0,0 +1,365 @@
+use crate::rust_component::{RustComponent, RustComponentTrait};
+use crate::{RustFile, RustImplementation, RustModule};
+
+use std::error::Error;
+use std::fmt;
+
+/// A component which is defined differently by both trees being merged. The path is in the form
+/// accepted by [`RustFile::find`], e.g. `mod api::impl Foo::fn new`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Both trees define a component of the same kind and name which generate different code.
+    Duplicate { path: String },
+    /// Both trees define a method of the same name with different signatures.
+    SignatureMismatch {
+        path: String,
+        ours: String,
+        theirs: String,
+    },
+}
+
+/// Every conflict found while merging, nothing is merged if there are any.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct MergeError {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl RustFile {
+    /// Merges the components of `other` into this file. Imports and inner attributes are combined,
+    /// modules of the same name are merged recursively and inherent implementations with the same
+    /// header are merged into one. Components which generate the same code are only kept once,
+    /// while components of the same kind and name which differ are reported as conflicts, in which
+    /// case the file is left unchanged. The docstring and the top and bottom strings of `other` are
+    /// only used if this file doesn't have them.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustImplementation, RustMethod, RustModule};
+    ///
+    /// let fragment = |method: &str| {
+    ///     RustFile::new().with_import("use std::fmt").with_component(
+    ///         RustModule::new("api")
+    ///             .with_component(
+    ///                 RustImplementation::new("Foo")
+    ///                     .with_component(RustMethod::new(method).into())
+    ///                     .into(),
+    ///             )
+    ///             .into(),
+    ///     )
+    /// };
+    ///
+    /// let mut file = fragment("new");
+    /// file.merge(fragment("build")).unwrap();
+    ///
+    /// assert_eq!(
+    ///     file.into_rust_code(),
+    ///     "use std::fmt;\n\nmod api {\n    impl Foo {\n        fn new() {\n        }\n\n        fn build() {\n        }\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: RustFile) -> Result<(), MergeError> {
+        let mut components = self.components().clone();
+        let mut conflicts = Vec::new();
+
+        merge_components(
+            &mut components,
+            other.components().clone(),
+            "",
+            &mut conflicts,
+        );
+
+        if !conflicts.is_empty() {
+            return Err(MergeError { conflicts });
+        }
+
+        *self.components_mut() = components;
+
+        for import in other.imports() {
+            if !self.imports().contains(import) {
+                self.push_import(import);
+            }
+        }
+
+        for attribute in other.inner_attributes() {
+            if !self.inner_attributes().contains(attribute) {
+                self.push_inner_attribute(attribute);
+            }
+        }
+
+        self.fill_text_from(&other);
+
+        return Ok(());
+    }
+}
+
+impl RustModule {
+    /// Merges the imports and components of `other` into this module as described by
+    /// [`RustFile::merge`]. The name, visibility and attributes of this module are kept.
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, RustModule, RustStruct};
+    /// use rmod_gen::rust_component::Field;
+    ///
+    /// let mut module = RustModule::new("api").with_component(RustStruct::new("Foo").into());
+    /// let other = RustModule::new("api").with_component(
+    ///     RustStruct::new("Foo").with_field(Field::private("id", "u64")).into(),
+    /// );
+    ///
+    /// let error = module.merge(other).unwrap_err();
+    ///
+    /// assert_eq!(error.to_string(), "conflicting definitions of 'struct Foo'");
+    /// ```
+    pub fn merge(&mut self, other: RustModule) -> Result<(), MergeError> {
+        let mut components = self.components().clone();
+        let mut conflicts = Vec::new();
+
+        merge_components(
+            &mut components,
+            other.components().clone(),
+            "",
+            &mut conflicts,
+        );
+
+        if !conflicts.is_empty() {
+            return Err(MergeError { conflicts });
+        }
+
+        self.set_components(components);
+        merge_imports(self, &other);
+
+        return Ok(());
+    }
+}
+
+impl MergeError {
+    /// The conflicts which prevented the merge, in the order they were found.
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        return &self.conflicts;
+    }
+}
+
+impl MergeConflict {
+    /// The path of the conflicting component.
+    pub fn path(&self) -> &str {
+        return match self {
+            MergeConflict::Duplicate { path } | MergeConflict::SignatureMismatch { path, .. } => {
+                path
+            }
+        };
+    }
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            MergeConflict::Duplicate { path } => {
+                write!(f, "conflicting definitions of '{}'", path)
+            }
+            MergeConflict::SignatureMismatch { path, ours, theirs } => write!(
+                f,
+                "conflicting signatures of '{}': '{}' and '{}'",
+                path, ours, theirs
+            ),
+        };
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", conflict)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl Error for MergeError {}
+
+fn merge_components(
+    ours: &mut Vec<RustComponent>,
+    theirs: Vec<RustComponent>,
+    path: &str,
+    conflicts: &mut Vec<MergeConflict>,
+) {
+    for component in theirs {
+        let segment = component.path_segment();
+        let component_path = if path.is_empty() {
+            segment.clone()
+        } else {
+            format!("{}::{}", path, segment)
+        };
+
+        let existing = match &component {
+            // Text has no name, so it is only compared with identical text.
+            RustComponent::Text(b) => ours.iter().position(|c| match c {
+                RustComponent::Text(a) => a.text() == b.text(),
+                _ => false,
+            }),
+            // Inherent implementations with different generics can coexist.
+            RustComponent::Implementation(b) if is_inherent(b) => {
+                ours.iter().position(|c| match c {
+                    RustComponent::Implementation(a) => header(a) == header(b),
+                    _ => false,
+                })
+            }
+            _ => ours.iter().position(|c| c.path_segment() == segment),
+        };
+
+        let Some(index) = existing else {
+            ours.push(component);
+            continue;
+        };
+
+        match (&mut ours[index], component) {
+            (RustComponent::Module(a), RustComponent::Module(b)) => {
+                merge_imports(a, &b);
+                merge_components(
+                    a.components_mut(),
+                    b.components().clone(),
+                    &component_path,
+                    conflicts,
+                );
+            }
+            (RustComponent::Implementation(a), RustComponent::Implementation(b))
+                if is_inherent(a) =>
+            {
+                merge_components(
+                    a.components_mut(),
+                    b.components().clone(),
+                    &component_path,
+                    conflicts,
+                );
+            }
+            (RustComponent::Method(a), RustComponent::Method(b))
+                if a.signature() != b.signature() =>
+            {
+                conflicts.push(MergeConflict::SignatureMismatch {
+                    path: component_path,
+                    ours: a.signature(),
+                    theirs: b.signature(),
+                });
+            }
+            (a, b) => {
+                if a.to_rust_string(0) != b.to_rust_string(0) {
+                    conflicts.push(MergeConflict::Duplicate {
+                        path: component_path,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn merge_imports(ours: &mut RustModule, theirs: &RustModule) {
+    for import in theirs.imports() {
+        if !ours.imports().contains(import) {
+            ours.push_import(import);
+        }
+    }
+}
+
+/// Whether the implementation is not of a trait, and so can be split across several blocks.
+fn is_inherent(implementation: &RustImplementation) -> bool {
+    return !implementation.name().contains(" for ");
+}
+
+/// The implementation without any components, e.g. `impl<T> Foo<T> {\n}`.
+fn header(implementation: &RustImplementation) -> String {
+    let mut implementation = implementation.clone();
+
+    implementation.components_mut().clear();
+
+    return implementation.to_rust_string(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Field;
+    use crate::{RustMethod, RustStruct, RustText};
+
+    fn fragment(components: Vec<RustComponent>) -> RustFile {
+        return RustFile::new().with_component(
+            RustModule::new("api")
+                .with_component(
+                    components
+                        .into_iter()
+                        .fold(RustImplementation::new("Foo"), |i, c| i.with_component(c))
+                        .into(),
+                )
+                .into(),
+        );
+    }
+
+    #[test]
+    fn test_merge_identical() {
+        let mut file = fragment(vec![RustMethod::new("new").into()])
+            .with_component(RustText::new("// generated").into());
+        let other = file.clone();
+
+        file.merge(other.clone()).unwrap();
+
+        assert_eq!(file, other);
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let mut file = fragment(vec![
+            RustMethod::new("new").into(),
+            RustMethod::new("id").with_body("1").into(),
+        ])
+        .with_component(RustStruct::new("Foo").into());
+        let original = file.clone();
+
+        let error = file
+            .merge(
+                fragment(vec![
+                    RustMethod::new("new").with_argument("id: u64").into(),
+                    RustMethod::new("id").with_body("2").into(),
+                ])
+                .with_component(
+                    RustStruct::new("Foo")
+                        .with_field(Field::private("id", "u64"))
+                        .into(),
+                ),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            error.conflicts(),
+            &[
+                MergeConflict::SignatureMismatch {
+                    path: String::from("mod api::impl Foo::fn new"),
+                    ours: String::from("fn new();"),
+                    theirs: String::from("fn new(id: u64);"),
+                },
+                MergeConflict::Duplicate {
+                    path: String::from("mod api::impl Foo::fn id"),
+                },
+                MergeConflict::Duplicate {
+                    path: String::from("struct Foo"),
+                },
+            ]
+        );
+        assert_eq!(file, original);
+    }
+
+    #[test]
+    fn test_merge_generic_implementations() {
+        let mut file = RustFile::new().with_component(RustImplementation::new("Foo").into());
+
+        file.merge(
+            RustFile::new().with_component(
+                RustImplementation::new("Foo")
+                    .with_target_template("u8")
+                    .into(),
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(file.components().len(), 2);
+    }
+}
@@ -0,0 +1,700 @@
+use std::error::Error;
+use std::fmt;
+
+use syn::parse_quote;
+
+use crate::rust_component::{Field, RustComponent, Visibility};
+use crate::{
+    EnumVariant, RustEnum, RustFile, RustImplementation, RustMethod, RustModule, RustStruct,
+    RustText, RustTrait, RustVariable, TraitItem,
+};
+
+/// An error produced when some source code could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+impl ParseError {
+    /// A description of what went wrong.
+    pub fn message(&self) -> &str {
+        return &self.message;
+    }
+
+    /// The line, starting at 1, at which the error occurred.
+    pub fn line(&self) -> usize {
+        return self.line;
+    }
+
+    /// The column, starting at 0, at which the error occurred.
+    pub fn column(&self) -> usize {
+        return self.column;
+    }
+}
+
+impl From<syn::Error> for ParseError {
+    fn from(error: syn::Error) -> Self {
+        let start = error.span().start();
+
+        return Self {
+            message: error.to_string(),
+            line: start.line,
+            column: start.column,
+        };
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}:{}: {}", self.line, self.column, self.message);
+    }
+}
+
+impl Error for ParseError {}
+
+impl RustFile {
+    /// Parses Rust source code into a file of components so that it can be edited and generated
+    /// again. Structs, enums, functions, impl blocks, traits, inline modules, consts and statics
+    /// become the corresponding components and `use` declarations become imports. Items which
+    /// can't be represented by a component, for example tuple structs or items with attributes on
+    /// their fields, are kept as [`RustText`].
+    ///
+    /// Only available with the `parse` feature. Comments other than doc comments are not preserved.
+    ///
+    /// ```
+    /// use rmod_gen::RustFile;
+    ///
+    /// let file = RustFile::parse("use std::fmt;\n\npub struct Cow {\n    pub age: u64,\n}\n").unwrap();
+    ///
+    /// assert_eq!(file.into_rust_code(), "use std::fmt;\n\npub struct Cow {\n    pub age: u64,\n}\n");
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let file = syn::parse_file(source)?;
+        let mut rust_file = RustFile::new();
+
+        let docs = docs(&file.attrs);
+        if !docs.is_empty() {
+            rust_file = rust_file.with_file_docstring(&docs);
+        }
+
+        for attr in file
+            .attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("doc"))
+        {
+            let attribute = prettyplease::unparse(&syn::File {
+                shebang: None,
+                attrs: vec![attr.clone()],
+                items: Vec::new(),
+            });
+
+            rust_file.push_inner_attribute(attribute.trim_end());
+        }
+
+        for item in file.items {
+            match item {
+                syn::Item::Use(item) if item.attrs.is_empty() => {
+                    rust_file.push_import(&import_string(item));
+                }
+                item => rust_file.push_component(parse_item(item)),
+            }
+        }
+
+        return Ok(rust_file);
+    }
+}
+
+fn parse_item(item: syn::Item) -> RustComponent {
+    let component = match &item {
+        syn::Item::Struct(item) => parse_struct(item).map(Into::into),
+        syn::Item::Enum(item) => parse_enum(item).map(Into::into),
+        syn::Item::Fn(item) => {
+            parse_method(&item.attrs, &item.vis, &item.sig, Some(&item.block)).map(Into::into)
+        }
+        syn::Item::Impl(item) => parse_implementation(item).map(Into::into),
+        syn::Item::Trait(item) => parse_trait(item).map(Into::into),
+        syn::Item::Mod(item) => parse_module(item).map(Into::into),
+        syn::Item::Const(item) if item.attrs.is_empty() => parse_variable(
+            RustVariable::new_const(&item.ident.to_string()),
+            &item.vis,
+            &item.ty,
+        )
+        .map(|v| v.with_value(&expr_string(&item.expr)).into()),
+        syn::Item::Static(item) if item.attrs.is_empty() => {
+            let mut variable = RustVariable::new_static(&item.ident.to_string());
+            variable.set_mut(matches!(item.mutability, syn::StaticMutability::Mut(_)));
+
+            parse_variable(variable, &item.vis, &item.ty)
+                .map(|v| v.with_value(&expr_string(&item.expr)).into())
+        }
+        _ => None,
+    };
+
+    return component.unwrap_or_else(|| RustText::new(&unparse(item)).into());
+}
+
+fn parse_struct(item: &syn::ItemStruct) -> Option<RustStruct> {
+    let named = match &item.fields {
+        syn::Fields::Named(named) => named,
+        _ => return None,
+    };
+
+    let mut rust_struct = RustStruct::new(&item.ident.to_string())
+        .with_visibility(visibility(&item.vis)?)
+        .with_docs(&docs(&item.attrs));
+
+    for line in attribute_lines(&item.attrs) {
+        rust_struct = rust_struct.with_cfg(&line);
+    }
+
+    let (lifetimes, templates, extra) = generics(&item.generics);
+    lifetimes.iter().for_each(|l| rust_struct.push_lifetime(l));
+    templates.iter().for_each(|t| rust_struct.push_template(t));
+    rust_struct.set_extra(&extra);
+
+    for field in &named.named {
+        rust_struct.push_field(parse_field(field)?);
+    }
+
+    return Some(rust_struct);
+}
+
+fn parse_field(field: &syn::Field) -> Option<Field> {
+    if field.attrs.iter().any(|attr| !attr.path().is_ident("doc")) {
+        return None;
+    }
+
+    let name = field.ident.as_ref()?.to_string();
+
+    return Some(
+        Field::new(&name, &type_string(&field.ty), visibility(&field.vis)?)
+            .with_docs(&docs(&field.attrs)),
+    );
+}
+
+fn parse_enum(item: &syn::ItemEnum) -> Option<RustEnum> {
+    let mut rust_enum = RustEnum::new(&item.ident.to_string())
+        .with_visibility(visibility(&item.vis)?)
+        .with_docs(&docs(&item.attrs));
+
+    for line in attribute_lines(&item.attrs) {
+        rust_enum = rust_enum.with_cfg(&line);
+    }
+
+    let (lifetimes, templates, extra) = generics(&item.generics);
+    lifetimes.iter().for_each(|l| rust_enum.push_lifetime(l));
+    templates.iter().for_each(|t| rust_enum.push_template(t));
+    rust_enum.set_extra(&extra);
+
+    for variant in &item.variants {
+        if !variant.attrs.is_empty() || variant.discriminant.is_some() {
+            return None;
+        }
+
+        let name = variant.ident.to_string();
+
+        rust_enum.push_variant(match &variant.fields {
+            syn::Fields::Named(named) => EnumVariant::new_struct(
+                &name,
+                named
+                    .named
+                    .iter()
+                    .map(parse_field)
+                    .collect::<Option<Vec<Field>>>()?,
+            ),
+            syn::Fields::Unnamed(unnamed) => EnumVariant::new_value(
+                &name,
+                unnamed
+                    .unnamed
+                    .iter()
+                    .map(|field| type_string(&field.ty))
+                    .collect(),
+            ),
+            syn::Fields::Unit => EnumVariant::new_empty(&name),
+        });
+    }
+
+    return Some(rust_enum);
+}
+
+fn parse_method(
+    attrs: &[syn::Attribute],
+    vis: &syn::Visibility,
+    sig: &syn::Signature,
+    block: Option<&syn::Block>,
+) -> Option<RustMethod> {
+    if sig.variadic.is_some() {
+        return None;
+    }
+
+    let mut method = RustMethod::new(&sig.ident.to_string())
+        .with_visibility(visibility(vis)?)
+        .with_docs(&docs(attrs))
+        .with_cfg(&attribute_lines(attrs).join("\n"));
+
+    let mut fn_type = Vec::new();
+
+    if sig.constness.is_some() {
+        fn_type.push(String::from("const"));
+    }
+
+    if sig.asyncness.is_some() {
+        fn_type.push(String::from("async"));
+    }
+
+    if sig.unsafety.is_some() {
+        fn_type.push(String::from("unsafe"));
+    }
+
+    if let Some(abi) = &sig.abi {
+        fn_type.push(match &abi.name {
+            Some(name) => format!("extern \"{}\"", name.value()),
+            None => String::from("extern"),
+        });
+    }
+
+    method.set_fn_type(&fn_type.join(" "));
+
+    let (lifetimes, templates, extra) = generics(&sig.generics);
+    lifetimes.iter().for_each(|l| method.push_lifetime(l));
+    templates.iter().for_each(|t| method.push_template(t));
+    method.set_extra(&extra);
+
+    for argument in &sig.inputs {
+        method.push_argument(&extract(
+            unparse(parse_quote! { fn __rmod_gen(#argument) {} }),
+            "fn __rmod_gen(",
+            ") {}",
+        ));
+    }
+
+    if let syn::ReturnType::Type(_, tp) = &sig.output {
+        method.set_return_type(&type_string(tp));
+    }
+
+    match block {
+        Some(block) => method.set_body(&block_string(block)),
+        None => method.set_with_body(false),
+    }
+
+    return Some(method);
+}
+
+fn parse_implementation(item: &syn::ItemImpl) -> Option<RustImplementation> {
+    if item.defaultness.is_some() || item.generics.where_clause.is_some() {
+        return None;
+    }
+
+    let self_type = type_string(&item.self_ty);
+
+    let mut implementation = match &item.trait_ {
+        Some((None, path, _)) => {
+            RustImplementation::new_for(&type_string(&parse_quote! { #path }), &self_type)
+        }
+        Some((Some(_), _, _)) => return None,
+        None => RustImplementation::new(&self_type),
+    };
+
+    if item.unsafety.is_some() {
+        implementation.set_extra("unsafe");
+    }
+
+    let (lifetimes, templates, _) = generics(&item.generics);
+    lifetimes
+        .iter()
+        .for_each(|l| implementation.push_impl_lifetime(l));
+    templates
+        .iter()
+        .for_each(|t| implementation.push_impl_template(t));
+
+    for impl_item in &item.items {
+        let component: Option<RustComponent> = match impl_item {
+            syn::ImplItem::Fn(f) if f.defaultness.is_none() => {
+                parse_method(&f.attrs, &f.vis, &f.sig, Some(&f.block)).map(Into::into)
+            }
+            syn::ImplItem::Const(c)
+                if c.attrs.is_empty()
+                    && c.defaultness.is_none()
+                    && c.generics.params.is_empty() =>
+            {
+                parse_variable(RustVariable::new_const(&c.ident.to_string()), &c.vis, &c.ty)
+                    .map(|v| v.with_value(&expr_string(&c.expr)).into())
+            }
+            _ => None,
+        };
+
+        implementation.push_component(component.unwrap_or_else(|| {
+            RustText::new(&extract_block_item(unparse(parse_quote! {
+                impl __RmodGen {
+                    #impl_item
+                }
+            })))
+            .into()
+        }));
+    }
+
+    return Some(implementation);
+}
+
+fn parse_trait(item: &syn::ItemTrait) -> Option<RustTrait> {
+    if item.unsafety.is_some() || item.auto_token.is_some() || item.restriction.is_some() {
+        return None;
+    }
+
+    let mut rust_trait =
+        RustTrait::new(&item.ident.to_string()).with_visibility(visibility(&item.vis)?);
+
+    rust_trait.set_cfg(&attribute_lines(&item.attrs).join("\n"));
+
+    let (lifetimes, templates, extra) = generics(&item.generics);
+    lifetimes.iter().for_each(|l| rust_trait.push_lifetime(l));
+    templates.iter().for_each(|t| rust_trait.push_template(t));
+    rust_trait.set_extra(&extra);
+
+    for bound in &item.supertraits {
+        rust_trait.push_bound(&bound_string(bound));
+    }
+
+    for trait_item in &item.items {
+        let parsed = match trait_item {
+            syn::TraitItem::Fn(f) => parse_method(
+                &f.attrs,
+                &syn::Visibility::Inherited,
+                &f.sig,
+                f.default.as_ref(),
+            )
+            .map(|method| match f.default {
+                Some(_) => TraitItem::new_provided(method),
+                None => TraitItem::new_required(method),
+            }),
+            syn::TraitItem::Type(t) if t.attrs.is_empty() && t.generics.params.is_empty() => {
+                Some(TraitItem::new_type(
+                    &t.ident.to_string(),
+                    t.bounds.iter().map(bound_string).collect(),
+                    t.default.as_ref().map(|(_, tp)| type_string(tp)).as_deref(),
+                ))
+            }
+            syn::TraitItem::Const(c) if c.attrs.is_empty() && c.generics.params.is_empty() => {
+                Some(TraitItem::new_const(
+                    &c.ident.to_string(),
+                    &type_string(&c.ty),
+                    c.default.as_ref().map(|(_, e)| expr_string(e)).as_deref(),
+                ))
+            }
+            _ => None,
+        };
+
+        rust_trait.push_item(parsed.unwrap_or_else(|| {
+            TraitItem::Other(
+                RustText::new(&extract_block_item(unparse(parse_quote! {
+                    trait __RmodGen {
+                        #trait_item
+                    }
+                })))
+                .into(),
+            )
+        }));
+    }
+
+    return Some(rust_trait);
+}
+
+fn parse_module(item: &syn::ItemMod) -> Option<RustModule> {
+    let (_, items) = item.content.as_ref()?;
+
+    if item.unsafety.is_some() {
+        return None;
+    }
+
+    let mut module =
+        RustModule::new(&item.ident.to_string()).with_visibility(visibility(&item.vis)?);
+
+    module.set_cfg(&attribute_lines(&item.attrs).join("\n"));
+
+    for item in items.iter().cloned() {
+        match item {
+            syn::Item::Use(item) if item.attrs.is_empty() => {
+                module.push_import(&import_string(item));
+            }
+            item => module.push_component(parse_item(item)),
+        }
+    }
+
+    return Some(module);
+}
+
+fn parse_variable(
+    variable: RustVariable,
+    vis: &syn::Visibility,
+    tp: &syn::Type,
+) -> Option<RustVariable> {
+    return Some(
+        variable
+            .with_visibility(visibility(vis)?)
+            .with_type(&type_string(tp)),
+    );
+}
+
+/// Converts a visibility, returning `None` for visibilities without a corresponding [`Visibility`],
+/// e.g. `pub(super)`.
+fn visibility(vis: &syn::Visibility) -> Option<Visibility> {
+    return match vis {
+        syn::Visibility::Public(_) => Some(Visibility::Public),
+        syn::Visibility::Inherited => Some(Visibility::Private),
+        syn::Visibility::Restricted(restricted)
+            if restricted.in_token.is_none() && restricted.path.is_ident("crate") =>
+        {
+            Some(Visibility::CrateVisible)
+        }
+        syn::Visibility::Restricted(_) => None,
+    };
+}
+
+/// Splits generics into lifetimes, without the leading `'`, templates and a where clause.
+fn generics(generics: &syn::Generics) -> (Vec<String>, Vec<String>, String) {
+    let mut lifetimes = Vec::new();
+    let mut templates = Vec::new();
+
+    for param in &generics.params {
+        let param_string = extract(
+            unparse(parse_quote! { struct __RmodGen<#param>; }),
+            "struct __RmodGen<",
+            ">;",
+        );
+
+        match param {
+            syn::GenericParam::Lifetime(_) => {
+                lifetimes.push(param_string.trim_start_matches('\'').to_string())
+            }
+            _ => templates.push(param_string),
+        }
+    }
+
+    let where_clause = match &generics.where_clause {
+        Some(where_clause) if !where_clause.predicates.is_empty() => {
+            let predicates: Vec<String> = where_clause
+                .predicates
+                .iter()
+                .map(|predicate| {
+                    let s = unparse(parse_quote! { fn __rmod_gen() where #predicate {} });
+
+                    s.lines()
+                        .skip_while(|l| l.trim() != "where")
+                        .skip(1)
+                        .take_while(|l| l.trim() != "{}")
+                        .map(str::trim)
+                        .collect::<Vec<&str>>()
+                        .join(" ")
+                        .trim_end_matches(',')
+                        .to_string()
+                })
+                .collect();
+
+            format!("where {}", predicates.join(", "))
+        }
+        _ => String::new(),
+    };
+
+    return (lifetimes, templates, where_clause);
+}
+
+/// The text of the doc comments within some attributes.
+fn docs(attrs: &[syn::Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.lines()
+                .map(|l| l.strip_prefix(' ').unwrap_or(l).to_string())
+                .collect::<Vec<String>>()
+        })
+        .collect();
+
+    return lines.join("\n");
+}
+
+/// The lines of all attributes except doc comments.
+fn attribute_lines(attrs: &[syn::Attribute]) -> Vec<String> {
+    let attrs: Vec<&syn::Attribute> = attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("doc"))
+        .collect();
+
+    let s = unparse(parse_quote! {
+        #(#attrs)*
+        struct __RmodGen;
+    });
+
+    return s
+        .lines()
+        .take_while(|l| *l != "struct __RmodGen;")
+        .map(String::from)
+        .collect();
+}
+
+fn import_string(item: syn::ItemUse) -> String {
+    return unparse(item.into())
+        .trim_end()
+        .trim_end_matches(';')
+        .to_string();
+}
+
+fn type_string(tp: &syn::Type) -> String {
+    return extract(
+        unparse(parse_quote! { type __RmodGen = #tp; }),
+        "type __RmodGen = ",
+        ";",
+    );
+}
+
+fn expr_string(expr: &syn::Expr) -> String {
+    return extract(
+        unparse(parse_quote! { const __RMOD_GEN: () = #expr; }),
+        "const __RMOD_GEN: () = ",
+        ";",
+    );
+}
+
+fn bound_string(bound: &syn::TypeParamBound) -> String {
+    return extract(
+        unparse(parse_quote! { fn __rmod_gen<T: #bound>() {} }),
+        "fn __rmod_gen<T: ",
+        ">() {}",
+    );
+}
+
+/// The statements of a block, without the surrounding braces or indentation.
+fn block_string(block: &syn::Block) -> String {
+    return extract_block_item(unparse(parse_quote! { fn __rmod_gen() #block }));
+}
+
+/// The contents of a generated item with a block, e.g. `impl __RmodGen { ... }`, with one level
+/// of indentation removed.
+fn extract_block_item(s: String) -> String {
+    let lines: Vec<&str> = s.lines().collect();
+
+    if lines.len() <= 2 {
+        return String::new();
+    }
+
+    return lines[1..lines.len() - 1]
+        .iter()
+        .map(|l| format!("{}\n", l.strip_prefix("    ").unwrap_or(l)))
+        .collect();
+}
+
+/// Removes the text surrounding a formatted fragment.
+fn extract(s: String, prefix: &str, suffix: &str) -> String {
+    let trimmed = s.trim_end();
+
+    return trimmed
+        .strip_prefix(prefix)
+        .and_then(|s| s.strip_suffix(suffix))
+        .unwrap_or(trimmed)
+        .to_string();
+}
+
+fn unparse(item: syn::Item) -> String {
+    return prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item],
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(source: &str) -> String {
+        return RustFile::parse(source).unwrap().to_rust_code();
+    }
+
+    #[test]
+    fn test_parse_struct() {
+        let source = "/// A cow.\n#[derive(Debug)]\npub struct Cow<'a, T: Clone> where T: Default {\n    /// The name.\n    pub(crate) name: &'a str,\n    value: Vec<T>,\n}\n";
+
+        assert_eq!(
+            round_trip(source),
+            "/// A cow.\n#[derive(Debug)]\npub struct Cow<'a, T: Clone> where T: Default {\n    /// The name.\n    pub(crate) name: &'a str,\n    value: Vec<T>,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_enum() {
+        let source = "enum Animal {\n    Cow { age: u8 },\n    Sheep(String, u8),\n    Empty,\n}\n";
+
+        assert_eq!(
+            round_trip(source),
+            "enum Animal {\n    Cow {\n        age: u8,\n    },\n    Sheep(String, u8),\n    Empty,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_method() {
+        let source = "/// Adds.\n#[inline]\npub const fn add<'a, T: Copy>(&self, a: &'a mut T) -> Option<T> where T: Default {\n    let b = *a;\n\n    Some(b)\n}\n";
+
+        assert_eq!(
+            round_trip(source),
+            "/// Adds.\n#[inline]\npub const fn add<'a, T: Copy>(&self, a: &'a mut T) -> Option<T> where T: Default {\n    let b = *a;\n    Some(b)\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_impl_and_trait() {
+        let source = "trait Shape: Debug {\n    type Unit: Copy;\n    const SIDES: u8 = 4;\n    fn area(&self) -> f64;\n}\n\nimpl<T> Shape for Square<T> {\n    type Unit = u8;\n    fn area(&self) -> f64 {\n        1.0\n    }\n}\n";
+
+        assert_eq!(
+            round_trip(source),
+            "trait Shape: Debug {\n    type Unit: Copy;\n    const SIDES: u8 = 4;\n\n    fn area(&self) -> f64;\n}\n\nimpl<T> Shape for Square<T> {\n    type Unit = u8;\n\n    fn area(&self) -> f64 {\n        1.0\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_module_and_variables() {
+        let file = RustFile::parse(
+            "//! Docs.\n#![allow(dead_code)]\nuse std::fmt;\n\nmod inner {\n    use super::*;\n\n    pub fn f() {}\n}\n\npub static mut COUNT: u32 = 0;\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.to_rust_code(),
+            "//! Docs.\n\n#![allow(dead_code)]\n\nuse std::fmt;\n\nmod inner {\n    use super::*;\n\n    pub fn f() {\n    }\n}\n\npub static mut COUNT: u32 = 0;\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_unsupported_item_as_text() {
+        assert_eq!(
+            round_trip("pub struct Meters(pub f64);\n\nmacro_rules! m {\n    () => {};\n}\n"),
+            "pub struct Meters(pub f64);\n\nmacro_rules! m {\n    () => {};\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_is_stable() {
+        let source = "pub(crate) enum E<'a> {\n    A(&'a str),\n}\n\nimpl<'a> E<'a> {\n    pub fn a(&self) -> &'a str {\n        match self {\n            E::A(s) => s,\n        }\n    }\n}\n";
+        let once = round_trip(source);
+
+        assert_eq!(round_trip(&once), once);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = RustFile::parse("struct {").unwrap_err();
+
+        assert_eq!(error.line(), 1);
+        assert_eq!(error.column(), 7);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::rust_component::RustComponent;
+use crate::{RustFile, RustImplementation, RustModule};
+
+use std::error::Error;
+use std::fmt;
+
+/// The keywords which can start a segment of a path, selecting a component by its kind as well as
+/// its name, e.g. `impl Foo` or `fn new`.
+const KEYWORDS: [&str; 9] = [
+    "mod", "struct", "enum", "fn", "impl", "trait", "const", "static", "let",
+];
+
+/// The reasons why a component cannot be added at a path.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum PathError {
+    /// The path contains an empty segment, e.g. `api::::Foo`.
+    EmptySegment { path: String },
+    /// A segment of the path names a component which cannot contain other components, such as a
+    /// method or a trait.
+    NotAContainer { path: String, segment: String },
+}
+
+impl RustFile {
+    /// The component at `path`, where the segments of the path are separated by `::`.
+    ///
+    /// A segment is either the name of a component, e.g. `api` or `Foo`, or a keyword followed by
+    /// the name, e.g. `mod api`, `impl Foo`, `impl Display for Foo` or `fn new`. Plain names never
+    /// match implementations. A plain name followed by further segments looks inside the module of
+    /// that name or, for a struct or enum, inside its inherent implementation, so `api::Foo::new`
+    /// is the method `new` in `impl Foo` within `mod api`.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustImplementation, RustMethod, RustModule, RustStruct};
+    /// use rmod_gen::rust_component::RustComponent;
+    ///
+    /// let file = RustFile::new().with_component(
+    ///     RustModule::new("api")
+    ///         .with_component(RustStruct::new("Foo").into())
+    ///         .with_component(
+    ///             RustImplementation::new("Foo")
+    ///                 .with_component(RustMethod::new("new").into())
+    ///                 .into(),
+    ///         )
+    ///         .into(),
+    /// );
+    ///
+    /// assert!(matches!(file.find("api::Foo"), Some(RustComponent::Struct(_))));
+    /// assert!(matches!(file.find("api::impl Foo"), Some(RustComponent::Implementation(_))));
+    /// assert!(matches!(file.find("api::Foo::new"), Some(RustComponent::Method(_))));
+    /// assert!(file.find("api::Bar").is_none());
+    /// ```
+    pub fn find(&self, path: &str) -> Option<&RustComponent> {
+        let segments = split_path(path)?;
+        let (last, parents) = segments.split_last()?;
+        let mut components = self.components();
+
+        for segment in parents {
+            let index = container_position(components, segment)?;
+
+            components = children(&components[index])?;
+        }
+
+        return components.iter().find(|c| matches(c, last));
+    }
+
+    /// The component at `path` as described by [`RustFile::find`], which can be edited in place.
+    pub fn find_mut(&mut self, path: &str) -> Option<&mut RustComponent> {
+        let segments = split_path(path)?;
+        let (last, parents) = segments.split_last()?;
+        let mut components = self.components_mut();
+
+        for segment in parents {
+            let index = container_position(components, segment)?;
+
+            components = children_mut(&mut components[index])?;
+        }
+
+        return components.iter_mut().find(|c| matches(c, last));
+    }
+
+    /// Adds `component` to the module or implementation at `path`, replacing a component of the
+    /// same kind and name. Modules and implementations along the path which don't exist yet are
+    /// created: segments such as `impl Foo` create an implementation and plain names create a
+    /// module, unless they name a struct or enum, in which case its inherent implementation is
+    /// used. An empty path adds the component to the root of the file.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustMethod, RustStruct};
+    ///
+    /// let mut file = RustFile::new();
+    ///
+    /// file.upsert("api", RustStruct::new("Foo").into()).unwrap();
+    /// file.upsert("api::Foo", RustMethod::new("new").into()).unwrap();
+    /// file.upsert("api::impl Foo", RustMethod::new("new").with_body("todo!()").into()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     file.into_rust_code(),
+    ///     "mod api {\n    struct Foo {\n    }\n\n    impl Foo {\n        fn new() {\n            todo!()\n        }\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn upsert(
+        &mut self,
+        path: &str,
+        component: RustComponent,
+    ) -> Result<&mut RustComponent, PathError> {
+        let segments = if path.trim().is_empty() {
+            Vec::new()
+        } else {
+            split_path(path).ok_or_else(|| PathError::EmptySegment {
+                path: path.to_string(),
+            })?
+        };
+
+        let mut components = self.components_mut();
+
+        for segment in segments {
+            let index = match container_position(components, segment) {
+                Some(index) => index,
+                None => {
+                    let container = new_container(components, segment).ok_or_else(|| {
+                        PathError::NotAContainer {
+                            path: path.to_string(),
+                            segment: segment.to_string(),
+                        }
+                    })?;
+
+                    components.push(container);
+                    components.len() - 1
+                }
+            };
+
+            components = children_mut(&mut components[index]).expect("containers have children");
+        }
+
+        let existing = match component.name() {
+            Some(_) => {
+                let segment = component.path_segment();
+
+                components.iter().position(|c| c.path_segment() == segment)
+            }
+            None => None,
+        };
+
+        let index = match existing {
+            Some(index) => {
+                components[index] = component;
+                index
+            }
+            None => {
+                components.push(component);
+                components.len() - 1
+            }
+        };
+
+        return Ok(&mut components[index]);
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            PathError::EmptySegment { path } => write!(f, "'{}' contains an empty segment", path),
+            PathError::NotAContainer { path, segment } => write!(
+                f,
+                "cannot add components to '{}' in '{}', it is not a module or implementation",
+                segment, path
+            ),
+        };
+    }
+}
+
+impl Error for PathError {}
+
+/// The trimmed segments of a path, or `None` if any of them are empty.
+fn split_path(path: &str) -> Option<Vec<&str>> {
+    let segments: Vec<&str> = path.split("::").map(str::trim).collect();
+
+    if segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+
+    return Some(segments);
+}
+
+/// Splits a segment such as `impl Foo` into its keyword and name.
+fn keyword(segment: &str) -> Option<(&str, &str)> {
+    let (keyword, name) = segment.split_once(' ')?;
+
+    if !KEYWORDS.contains(&keyword) {
+        return None;
+    }
+
+    return Some((keyword, name.trim()));
+}
+
+fn matches(component: &RustComponent, segment: &str) -> bool {
+    if keyword(segment).is_some() {
+        return component.path_segment() == segment;
+    }
+
+    return !matches!(component, RustComponent::Implementation(_))
+        && component.name() == Some(segment);
+}
+
+/// The position of the module or implementation a segment refers to when it is followed by more
+/// segments.
+fn container_position(components: &[RustComponent], segment: &str) -> Option<usize> {
+    let name = match keyword(segment) {
+        Some(("mod", _)) | Some(("impl", _)) => {
+            return components
+                .iter()
+                .position(|c| children(c).is_some() && c.path_segment() == segment);
+        }
+        Some(("struct", name)) | Some(("enum", name)) => name,
+        Some(_) => return None,
+        None => {
+            let module = components.iter().position(|c| match c {
+                RustComponent::Module(module) => module.name() == segment,
+                _ => false,
+            });
+
+            if module.is_some() {
+                return module;
+            }
+
+            segment
+        }
+    };
+
+    if !components.iter().any(|c| is_type(c, name)) {
+        return None;
+    }
+
+    return components.iter().position(|c| match c {
+        RustComponent::Implementation(implementation) => implementation.name() == name,
+        _ => false,
+    });
+}
+
+/// Creates the module or implementation a segment refers to, or `None` if the segment can't
+/// contain other components.
+fn new_container(components: &[RustComponent], segment: &str) -> Option<RustComponent> {
+    return match keyword(segment) {
+        Some(("mod", name)) => Some(RustModule::new(name).into()),
+        Some(("impl", name)) | Some(("struct", name)) | Some(("enum", name)) => {
+            Some(RustImplementation::new(name).into())
+        }
+        Some(_) => None,
+        None if components.iter().any(|c| is_type(c, segment)) => {
+            Some(RustImplementation::new(segment).into())
+        }
+        None if components.iter().any(|c| matches(c, segment)) => None,
+        None => Some(RustModule::new(segment).into()),
+    };
+}
+
+/// Whether the component is a struct or enum called `name`.
+fn is_type(component: &RustComponent, name: &str) -> bool {
+    return match component {
+        RustComponent::Struct(s) => s.name() == name,
+        RustComponent::Enum(e) => e.name() == name,
+        _ => false,
+    };
+}
+
+fn children(component: &RustComponent) -> Option<&Vec<RustComponent>> {
+    return match component {
+        RustComponent::Module(module) => Some(module.components()),
+        RustComponent::Implementation(implementation) => Some(implementation.components()),
+        _ => None,
+    };
+}
+
+fn children_mut(component: &mut RustComponent) -> Option<&mut Vec<RustComponent>> {
+    return match component {
+        RustComponent::Module(module) => Some(module.components_mut()),
+        RustComponent::Implementation(implementation) => Some(implementation.components_mut()),
+        _ => None,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RustMethod, RustStruct, RustTrait};
+
+    fn file() -> RustFile {
+        return RustFile::new().with_component(
+            RustModule::new("api")
+                .with_component(RustStruct::new("Foo").into())
+                .with_component(
+                    RustImplementation::new("Display for Foo")
+                        .with_component(RustMethod::new("fmt").into())
+                        .into(),
+                )
+                .with_component(RustTrait::new("Bar").into())
+                .into(),
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let file = file();
+
+        assert_eq!(
+            file.find("api::impl Display for Foo::fn fmt")
+                .map(|c| c.path_segment()),
+            Some(String::from("fn fmt"))
+        );
+        assert!(matches!(
+            file.find("mod api::trait Bar"),
+            Some(RustComponent::Trait(_))
+        ));
+        assert!(file.find("api::Foo::fmt").is_none());
+        assert!(file.find("api::struct Bar").is_none());
+        assert!(file.find("api::::Foo").is_none());
+    }
+
+    #[test]
+    fn test_find_mut() {
+        let mut file = file();
+
+        if let Some(RustComponent::Struct(foo)) = file.find_mut("api::Foo") {
+            foo.set_name("Baz");
+        }
+
+        assert!(file.find("api::Foo").is_none());
+        assert!(file.find("api::struct Baz").is_some());
+    }
+
+    #[test]
+    fn test_upsert() {
+        let mut file = file();
+
+        file.upsert("api::Foo", RustMethod::new("new").into())
+            .unwrap();
+        file.upsert(
+            "api::Foo",
+            RustMethod::new("new").with_body("todo!()").into(),
+        )
+        .unwrap();
+        file.upsert("models::impl Model", RustMethod::new("id").into())
+            .unwrap();
+
+        assert!(matches!(
+            file.find("api::Foo::new"),
+            Some(RustComponent::Method(m)) if m.body() == "todo!()"
+        ));
+        assert_eq!(
+            file.find("api::impl Foo")
+                .map(|c| children(c).unwrap().len()),
+            Some(1)
+        );
+        assert!(file.find("models::impl Model::id").is_some());
+        assert_eq!(
+            file.upsert("api::Bar", RustMethod::new("new").into()),
+            Err(PathError::NotAContainer {
+                path: String::from("api::Bar"),
+                segment: String::from("Bar"),
+            })
+        );
+        assert_eq!(
+            file.upsert("api::fn new", RustMethod::new("new").into())
+                .unwrap_err()
+                .to_string(),
+            "cannot add components to 'fn new' in 'api::fn new', it is not a module or implementation"
+        );
+    }
+}
@@ -4,33 +4,233 @@ use crate::{
 };
 
 use crate::rust_text::RustText;
+use crate::source_map::SourceMapRecorder;
+use std::cell::RefCell;
 use std::fmt;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 /// Any rust component should implement this trait, it can then be used as sub-components for
-/// components which support it. It provides a method for converting a component into a string of Rust code.
+/// components which support it. It provides methods for writing a component as Rust code, either
+/// to any [`fmt::Write`] or into a string.
 pub trait RustComponentTrait: Into<RustComponent> {
+    /// Writes this object as rust code to `w`, indented and rendered as described by `ctx`.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::{RenderContext, RenderOptions, RustComponentTrait};
+    ///
+    /// let mut code = String::new();
+    /// RustStruct::new("Cow").write_rust(&mut code, &RenderContext::new(1, RenderOptions::new())).unwrap();
+    ///
+    /// assert_eq!(code, "    struct Cow {\n    }\n");
+    /// ```
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result;
+
     /// Represent this object as rust code indented to the desired level.
-    fn to_rust_string(&self, indent_level: usize) -> String;
+    fn to_rust_string(&self, indent_level: usize) -> String {
+        return self.to_rust_string_with(indent_level, &RenderOptions::new());
+    }
+
+    /// Represent this object as rust code indented to the desired level, rendered according to
+    /// `options`.
+    fn to_rust_string_with(&self, indent_level: usize, options: &RenderOptions) -> String {
+        let mut s = String::new();
+
+        self.write_rust(&mut s, &RenderContext::new(indent_level, *options))
+            .expect("writing to a String does not fail");
+
+        return s;
+    }
+}
+
+/// The state used while writing a component, the level of indentation and the options.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RenderContext<'a> {
+    indent_level: usize,
+    options: RenderOptions,
+    source_map: Option<&'a RefCell<SourceMapRecorder>>,
+}
+
+impl<'a> RenderContext<'a> {
+    /// Creates a new context for writing a component indented to `indent_level`.
+    pub fn new(indent_level: usize, options: RenderOptions) -> Self {
+        return Self {
+            indent_level,
+            options,
+            source_map: None,
+        };
+    }
+
+    /// Records the lines written by each component in `source_map`.
+    pub(crate) fn with_source_map(mut self, source_map: &'a RefCell<SourceMapRecorder>) -> Self {
+        self.source_map = Some(source_map);
+
+        return self;
+    }
+
+    /// The level of indentation of the component being written.
+    pub fn indent_level(&self) -> usize {
+        return self.indent_level;
+    }
+
+    /// The options used to render the component.
+    pub fn options(&self) -> &RenderOptions {
+        return &self.options;
+    }
+
+    pub(crate) fn source_map(&self) -> Option<&'a RefCell<SourceMapRecorder>> {
+        return self.source_map;
+    }
+
+    /// The context for a component nested one level within the current component.
+    pub fn nested(&self) -> Self {
+        return Self {
+            indent_level: self.indent_level + 1,
+            ..*self
+        };
+    }
+}
+
+/// Contexts are equal if they record to the same source map, if any.
+impl PartialEq for RenderContext<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let same_source_map = match (self.source_map, other.source_map) {
+            (Some(a), Some(b)) => std::ptr::eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+
+        return self.indent_level == other.indent_level
+            && self.options == other.options
+            && same_source_map;
+    }
+}
+
+impl Eq for RenderContext<'_> {}
+
+impl Hash for RenderContext<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.indent_level.hash(state);
+        self.options.hash(state);
+        self.source_map.map(|s| s as *const _).hash(state);
+    }
+}
+
+/// Options controlling how components are converted into Rust code.
+///
+/// ```
+/// use rmod_gen::RustMethod;
+/// use rmod_gen::rust_component::{RenderOptions, RustComponentTrait};
+///
+/// let method = RustMethod::new("area")
+///     .with_argument("width: f64")
+///     .with_argument("height: f64")
+///     .with_return_type("f64");
+///
+/// assert_eq!(
+///     method.to_rust_string_with(0, &RenderOptions::new().with_max_width(30)),
+///     "fn area(\n    width: f64,\n    height: f64,\n) -> f64 {\n}\n"
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct RenderOptions {
+    max_width: Option<usize>,
+    origin_comments: bool,
+}
+
+impl RenderOptions {
+    /// Creates the default options, with which lines are never wrapped.
+    pub fn new() -> Self {
+        return Self {
+            max_width: None,
+            origin_comments: false,
+        };
+    }
+
+    /// Sets the maximum width of a line. Function signatures, generic lists, where clauses,
+    /// tuple enum variants and struct literals in method bodies which are wider are wrapped with
+    /// one item per line in the style of rustfmt.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.set_max_width(Some(max_width));
+
+        return self;
+    }
+
+    /// Sets the maximum width of a line, `None` disables wrapping.
+    pub fn set_max_width(&mut self, max_width: Option<usize>) {
+        self.max_width = max_width;
+    }
+
+    /// Writes a comment such as `// generated from schema.json:42` before each component with an
+    /// origin.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::{RenderOptions, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Cow").with_origin("cows.json:3");
+    ///
+    /// assert_eq!(
+    ///     s.to_rust_string_with(0, &RenderOptions::new().with_origin_comments()),
+    ///     "// generated from cows.json:3\nstruct Cow {\n}\n"
+    /// );
+    /// ```
+    pub fn with_origin_comments(mut self) -> Self {
+        self.set_origin_comments(true);
+
+        return self;
+    }
+
+    /// Specifies whether a comment with the origin is written before each component with an
+    /// origin.
+    pub fn set_origin_comments(&mut self, origin_comments: bool) {
+        self.origin_comments = origin_comments;
+    }
+
+    pub(crate) fn origin_comments(&self) -> bool {
+        return self.origin_comments;
+    }
+
+    /// Returns true if `line` fits within the maximum width when indented to `indent_level`.
+    pub(crate) fn fits(&self, indent_level: usize, line: &str) -> bool {
+        return match self.max_width {
+            Some(max_width) => crate::line_width(indent_level, line) <= max_width,
+            None => true,
+        };
+    }
 }
 
 pub(crate) trait RustTemplateUsage {
-    fn create_template_string(templates: &Vec<String>, lifetimes: &Vec<String>) -> String {
+    /// The lifetimes, including the leading `'`, followed by the templates.
+    fn create_template_items(templates: &[String], lifetimes: &[String]) -> Vec<String> {
+        return lifetimes
+            .iter()
+            .map(|l| format!("'{}", l))
+            .chain(templates.iter().cloned())
+            .collect();
+    }
+
+    fn create_template_string(templates: &[String], lifetimes: &[String]) -> String {
         let templates: String = templates.join(", ");
         let lifetimes = Self::create_lifetime_string(lifetimes);
 
         if lifetimes.is_empty() && templates.is_empty() {
             return String::new();
-        } else if lifetimes.len() == 0 {
+        } else if lifetimes.is_empty() {
             return format!("<{}>", templates);
-        } else if templates.len() == 0 {
+        } else if templates.is_empty() {
             return format!("<{}>", lifetimes);
         } else {
             return format!("<{}, {}>", lifetimes, templates);
         }
     }
 
-    fn create_lifetime_string(lifetimes: &Vec<String>) -> String {
+    fn create_lifetime_string(lifetimes: &[String]) -> String {
         let mut res = String::new();
 
         for i in 0..lifetimes.len() {
@@ -48,14 +248,20 @@ pub(crate) trait RustTemplateUsage {
 
 /// Represents a field with a name, type and visibility level.
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field {
     name: String,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     field_type: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
+    docs: String,
 }
 
 /// RustComponent is the base type that is used across the library. Every component must have a RustComponent variant.
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RustComponent {
     Module(RustModule),
     Struct(RustStruct),
@@ -68,9 +274,11 @@ pub enum RustComponent {
     Trait(RustTrait),
 }
 
-/// Represents the 3 levels of visibility in Rust.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+/// Represents the 3 levels of visibility in Rust. Items are private by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Visibility {
+    #[default]
     Private,
     Public,
     /// pub(crate)
@@ -84,6 +292,7 @@ impl Field {
             name: name.to_string(),
             field_type: field_type.to_string(),
             visibility,
+            docs: String::new(),
         };
     }
 
@@ -92,12 +301,63 @@ impl Field {
         return Self::new(name, field_type, Visibility::Private);
     }
 
+    /// Documents the field with the specified text. Text should not contain any docstring lines.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::{Field, RustComponentTrait};
+    ///
+    /// let s = RustStruct::new("Cow").with_field(Field::private("age", "u64").with_docs("Age in years."));
+    ///
+    /// assert_eq!(s.to_rust_string(0), "struct Cow {\n    /// Age in years.\n    age: u64,\n}\n");
+    /// ```
+    pub fn with_docs(mut self, docs: &str) -> Self {
+        self.set_docs(docs);
+
+        return self;
+    }
+
+    /// Documents the field with the specified text. Text should not contain any docstring lines.
+    pub fn set_docs(&mut self, docs: &str) {
+        self.docs = docs.to_string();
+    }
+
+    /// Renames the field.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn set_field_type(&mut self, field_type: &str) {
+        self.field_type = field_type.to_string();
+    }
+
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = visibility;
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn docs(&self) -> &str {
+        return &self.docs;
+    }
+
+    pub fn field_type(&self) -> &str {
+        return &self.field_type;
+    }
+
     /// A fast version that doesn't involve any additional allocations.
     pub(crate) fn private_fast(name: String, field_type: String) -> Self {
         return Self {
             name,
             field_type,
             visibility: Visibility::Private,
+            docs: String::new(),
         };
     }
 }
@@ -113,21 +373,137 @@ impl fmt::Display for Field {
 }
 
 impl RustComponent {
+    pub fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        return match self {
+            RustComponent::Module(o) => o.write_rust(w, ctx),
+            RustComponent::Struct(o) => o.write_rust(w, ctx),
+            RustComponent::Enum(o) => o.write_rust(w, ctx),
+            RustComponent::EnumVariant(o) => o.write_rust(w, ctx),
+            RustComponent::Method(o) => o.write_rust(w, ctx),
+            RustComponent::Implementation(o) => o.write_rust(w, ctx),
+            RustComponent::Variable(o) => o.write_rust(w, ctx),
+            RustComponent::Text(o) => o.write_rust(w, ctx),
+            RustComponent::Trait(o) => o.write_rust(w, ctx),
+        };
+    }
+
     pub fn to_rust_string(&self, indent_level: usize) -> String {
+        return self.to_rust_string_with(indent_level, &RenderOptions::new());
+    }
+
+    pub fn to_rust_string_with(&self, indent_level: usize, options: &RenderOptions) -> String {
+        let mut s = String::new();
+
+        self.write_rust(&mut s, &RenderContext::new(indent_level, *options))
+            .expect("writing to a String does not fail");
+
+        return s;
+    }
+
+    /// The name of the component, e.g. the name of a struct, method or module. Implementations are
+    /// named after what they implement, e.g. `Foo` or `Display for Foo`, and text has no name.
+    pub fn name(&self) -> Option<&str> {
+        return match self {
+            RustComponent::Module(o) => Some(o.name()),
+            RustComponent::Struct(o) => Some(o.name()),
+            RustComponent::Enum(o) => Some(o.name()),
+            RustComponent::EnumVariant(o) => Some(o.name()),
+            RustComponent::Method(o) => Some(o.name()),
+            RustComponent::Implementation(o) => Some(o.name()),
+            RustComponent::Variable(o) => Some(o.name()),
+            RustComponent::Text(_) => None,
+            RustComponent::Trait(o) => Some(o.name()),
+        };
+    }
+
+    /// The component as part of a path, e.g. `struct Cow` or `impl Display for Cow`.
+    pub(crate) fn path_segment(&self) -> String {
+        return match self {
+            RustComponent::Module(o) => o.path_segment(),
+            RustComponent::Struct(o) => o.path_segment(),
+            RustComponent::Enum(o) => o.path_segment(),
+            RustComponent::EnumVariant(o) => o.name().to_string(),
+            RustComponent::Method(o) => o.path_segment(),
+            RustComponent::Implementation(o) => o.path_segment(),
+            RustComponent::Variable(o) => o.path_segment(),
+            RustComponent::Text(o) => o.path_segment(),
+            RustComponent::Trait(o) => o.path_segment(),
+        };
+    }
+
+    /// Whether the component takes a single line, e.g. a variable or a line of text.
+    pub(crate) fn is_single_line(&self) -> bool {
         return match self {
-            RustComponent::Module(o) => o.to_rust_string(indent_level),
-            RustComponent::Struct(o) => o.to_rust_string(indent_level),
-            RustComponent::Enum(o) => o.to_rust_string(indent_level),
-            RustComponent::EnumVariant(o) => o.to_rust_string(indent_level),
-            RustComponent::Method(o) => o.to_rust_string(indent_level),
-            RustComponent::Implementation(o) => o.to_rust_string(indent_level),
-            RustComponent::Variable(o) => o.to_rust_string(indent_level),
-            RustComponent::Text(o) => o.to_rust_string(indent_level),
-            RustComponent::Trait(o) => o.to_rust_string(indent_level),
+            RustComponent::Variable(_) => true,
+            RustComponent::Text(text) => !text.text().trim_end_matches('\n').contains('\n'),
+            _ => false,
         };
     }
 }
 
+/// Converts a snake case identifier such as `max_size` into pascal case, `MaxSize`.
+pub(crate) fn to_pascal_case(identifier: &str) -> String {
+    return identifier
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+
+            return match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            };
+        })
+        .collect();
+}
+
+/// The name of a template without any bounds, e.g. `T` for `T: Debug`.
+pub(crate) fn template_name(template: &str) -> &str {
+    return template.split(':').next().unwrap_or_default().trim();
+}
+
+/// Replaces every occurrence of the identifier `from` with `to`. Occurrences that form part of a
+/// longer identifier, or that are followed by a path separator such as `Self::Output`, are left
+/// as is.
+pub(crate) fn replace_identifier(s: &str, from: &str, to: &str) -> String {
+    return replace_word(s, from, to, false);
+}
+
+/// Replaces the identifier `from` with `to` where it starts a path, e.g. `T` in `T::Output`.
+pub(crate) fn replace_path_root(s: &str, from: &str, to: &str) -> String {
+    return replace_word(s, from, to, true);
+}
+
+/// Replaces the whole word occurrences of `from` which are followed by a path separator if
+/// `in_paths`, or the ones which aren't otherwise.
+fn replace_word(s: &str, from: &str, to: &str, in_paths: bool) -> String {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(position) = rest.find(from) {
+        let before = rest[..position].chars().next_back();
+        let after = &rest[position + from.len()..];
+
+        let whole_word = before.is_none_or(|c| !is_ident_char(c))
+            && after.chars().next().is_none_or(|c| !is_ident_char(c));
+
+        res.push_str(&rest[..position]);
+
+        if whole_word && after.starts_with("::") == in_paths {
+            res.push_str(to);
+        } else {
+            res.push_str(from);
+        }
+
+        rest = after;
+    }
+
+    res.push_str(rest);
+
+    return res;
+}
+
 impl std::fmt::Display for Visibility {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return write!(
@@ -146,6 +522,29 @@ impl std::fmt::Display for Visibility {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_to_pascal_case() {
+        assert_eq!(to_pascal_case("max_size"), "MaxSize");
+        assert_eq!(to_pascal_case("_id"), "Id");
+        assert_eq!(to_pascal_case("name"), "Name");
+    }
+
+    #[test]
+    fn test_replace_identifier() {
+        assert_eq!(
+            replace_identifier("&Self, other: T, Tx: Vec<T>", "T", "u32"),
+            "&Self, other: u32, Tx: Vec<u32>"
+        );
+        assert_eq!(
+            replace_identifier("Self::Output, Self, SelfRef", "Self", "Foo"),
+            "Self::Output, Foo, SelfRef"
+        );
+        assert_eq!(
+            replace_path_root("T::Item, T, Tx::Item", "T", "<u32 as Iterator>"),
+            "<u32 as Iterator>::Item, T, Tx::Item"
+        );
+    }
+
     mod test_rust_template_usage {
         use super::*;
 
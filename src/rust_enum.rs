@@ -1,6 +1,10 @@
 use crate::rust_component::{
-    Field, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
+    Field, RenderContext, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
 };
+use crate::source_map::{ComponentGuard, Origin};
+use crate::wrap::Header;
+
+use std::fmt;
 
 /// Represents an enum in rust.
 ///
@@ -41,18 +45,30 @@ use crate::rust_component::{
 ///  );
 /// ```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustEnum {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
     variants: Vec<EnumVariant>,
+    #[cfg_attr(feature = "serde", serde(default))]
     templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     extra: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     cfg: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    docs: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 /// Represents an enum variant in Rust. It supports Struct, Value and Empty variants.
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EnumVariant {
     /// Represents an enum variant that is a struct.
     /// ```
@@ -72,6 +88,7 @@ pub enum EnumVariant {
     StructVariant {
         name: String,
         /// Represents the fields for this struct variant. Field visibility is ignored when generating enums.
+        #[cfg_attr(feature = "serde", serde(default))]
         fields: Vec<Field>,
     },
     /// Represents an enum variant that is a value.
@@ -87,7 +104,11 @@ pub enum EnumVariant {
     ///
     /// let my_variant = EnumVariant::build("MyVariant").with_value("String").build();
     /// ```
-    ValueVariant { name: String, types: Vec<String> },
+    ValueVariant {
+        name: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        types: Vec<String>,
+    },
     /// Represents an enum variant that is simply a variant.
     /// ```
     /// use rmod_gen::EnumVariant;
@@ -124,6 +145,8 @@ impl RustEnum {
             lifetimes: Vec::new(),
             extra: String::new(),
             cfg: Vec::new(),
+            docs: String::new(),
+            origin: Origin::default(),
         };
     }
 
@@ -194,6 +217,22 @@ impl RustEnum {
         return self;
     }
 
+    /// Documents the enum with the specified text. Text should not contain any docstring lines.
+    ///
+    /// ```
+    /// use rmod_gen::RustEnum;
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_enum = RustEnum::new("Sound").with_docs("A sound.").to_rust_string(0);
+    ///
+    /// assert_eq!(rust_enum, "/// A sound.\nenum Sound {\n}\n");
+    /// ```
+    pub fn with_docs(mut self, docs: &str) -> Self {
+        self.set_docs(docs);
+
+        return self;
+    }
+
     /// Appends a new enum variant.
     pub fn push_variant(&mut self, variant: EnumVariant) {
         self.variants.push(variant);
@@ -253,6 +292,105 @@ impl RustEnum {
     pub fn set_cfg(&mut self, cfg: &str) {
         self.cfg = vec![cfg.to_string()];
     }
+
+    /// Documents the enum with the specified text. Text should not contain any docstring lines.
+    pub fn set_docs(&mut self, docs: &str) {
+        self.docs = docs.to_string();
+    }
+
+    /// Renames the enum.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Removes the variant called `name`, returning it if it existed.
+    ///
+    /// ```
+    /// use rmod_gen::{EnumVariant, RustEnum};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let mut rust_enum = RustEnum::new("Sound")
+    ///     .with_variant(EnumVariant::new_empty("Moo"))
+    ///     .with_variant(EnumVariant::new_empty("Baa"));
+    ///
+    /// rust_enum.remove_variant("Baa");
+    ///
+    /// assert_eq!(rust_enum.to_rust_string(0), "enum Sound {\n    Moo,\n}\n");
+    /// ```
+    pub fn remove_variant(&mut self, name: &str) -> Option<EnumVariant> {
+        let index = self.variants.iter().position(|v| v.name() == name)?;
+
+        return Some(self.variants.remove(index));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn variants(&self) -> &Vec<EnumVariant> {
+        return &self.variants;
+    }
+
+    /// The variants of the enum, which can be edited, reordered or removed in place.
+    pub fn variants_mut(&mut self) -> &mut Vec<EnumVariant> {
+        return &mut self.variants;
+    }
+
+    /// The variant called `name`.
+    pub fn find_variant(&self, name: &str) -> Option<&EnumVariant> {
+        return self.variants.iter().find(|v| v.name() == name);
+    }
+
+    pub fn find_variant_mut(&mut self, name: &str) -> Option<&mut EnumVariant> {
+        return self.variants.iter_mut().find(|v| v.name() == name);
+    }
+
+    pub fn templates(&self) -> &Vec<String> {
+        return &self.templates;
+    }
+
+    pub fn lifetimes(&self) -> &Vec<String> {
+        return &self.lifetimes;
+    }
+
+    pub fn extra(&self) -> &str {
+        return &self.extra;
+    }
+
+    /// The attributes placed before the enum, e.g. `#[derive(Clone)]`.
+    pub fn cfg(&self) -> &Vec<String> {
+        return &self.cfg;
+    }
+
+    pub fn docs(&self) -> &str {
+        return &self.docs;
+    }
+
+    /// Records where the enum was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the enum was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the enum was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The enum as part of a path in a source map, e.g. `enum Size`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("enum {}", self.name);
+    }
 }
 
 impl EnumVariant {
@@ -283,6 +421,23 @@ impl EnumVariant {
     pub fn build(name: &str) -> EnumVariantBuilder {
         return EnumVariantBuilder::new(name);
     }
+
+    pub fn name(&self) -> &str {
+        return match self {
+            EnumVariant::StructVariant { name, .. }
+            | EnumVariant::ValueVariant { name, .. }
+            | EnumVariant::EmptyVariant { name } => name,
+        };
+    }
+
+    /// Renames the variant.
+    pub fn set_name(&mut self, new_name: &str) {
+        match self {
+            EnumVariant::StructVariant { name, .. }
+            | EnumVariant::ValueVariant { name, .. }
+            | EnumVariant::EmptyVariant { name } => *name = new_name.to_string(),
+        }
+    }
 }
 
 impl EnumVariantBuilder {
@@ -364,41 +519,46 @@ impl Into<RustComponent> for RustEnum {
 impl RustTemplateUsage for RustEnum {}
 
 impl RustComponentTrait for RustEnum {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let mut lines = self.cfg.clone();
-
-        let crate_line = match self.visibility {
-            Visibility::Private => format!(
-                "enum {}{}",
-                self.name,
-                Self::create_template_string(&self.templates, &self.lifetimes)
-            ),
-            _ => format!(
-                "{} enum {}{}",
-                self.visibility,
-                self.name,
-                Self::create_template_string(&self.templates, &self.lifetimes)
-            ),
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        let indent_level = ctx.indent_level();
+
+        crate::write_docs(w, &self.docs, "///", indent_level)?;
+
+        for cfg in &self.cfg {
+            crate::write_indent(w, indent_level)?;
+            w.write_str(cfg)?;
+            w.write_char('\n')?;
+        }
+
+        let prefix = match self.visibility {
+            Visibility::Private => format!("enum {}", self.name),
+            _ => format!("{} enum {}", self.visibility, self.name),
         };
 
-        if self.extra.is_empty() {
-            lines.push(format!("{} {{", crate_line));
-        } else {
-            lines.push(format!("{} {} {{", crate_line, &self.extra));
+        let header = Header::new(&prefix, " {")
+            .with_generics(Self::create_template_items(
+                &self.templates,
+                &self.lifetimes,
+            ))
+            .with_extra(&self.extra);
+
+        for line in header.lines(indent_level, ctx.options()) {
+            crate::write_indent(w, indent_level)?;
+            w.write_str(&line)?;
+            w.write_char('\n')?;
         }
 
         for variant in &self.variants {
-            lines.push(variant.to_rust_string(indent_level + 1));
+            variant.write_rust(w, &ctx.nested())?;
+            w.write_char('\n')?;
         }
 
-        lines.push(String::from("}"));
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
 
-        let indent_str = crate::indent_string(indent_level);
-
-        return lines
-            .into_iter()
-            .map(|l| [indent_str.clone(), l, String::from("\n")].join(""))
-            .collect();
+        return Ok(());
     }
 }
 
@@ -409,35 +569,49 @@ impl Into<RustComponent> for EnumVariant {
 }
 
 impl RustComponentTrait for EnumVariant {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let indent_string = crate::indent_string(indent_level);
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let indent_level = ctx.indent_level();
 
-        return match self {
+        match self {
             EnumVariant::StructVariant { name, fields } => {
-                let nested_indent_string = crate::indent_string(indent_level + 1);
-                let f_str: String = fields
-                    .iter()
-                    .map(|f| format!("{}{},\n", &nested_indent_string, f))
-                    .collect();
-
-                format!(
-                    "{}{} {{\n{}{}}},",
-                    &indent_string, name, f_str, &indent_string
-                )
+                crate::write_indent(w, indent_level)?;
+                writeln!(w, "{} {{", name)?;
+
+                for field in fields {
+                    crate::write_docs(w, field.docs(), "///", indent_level + 1)?;
+                    crate::write_indent(w, indent_level + 1)?;
+                    writeln!(w, "{},", field)?;
+                }
+
+                crate::write_indent(w, indent_level)?;
+                w.write_str("},")?;
             }
             EnumVariant::ValueVariant { name, types } => {
-                format!("{}{}({}),", indent_string, name, types.join(", "))
+                let lines = crate::wrap::list(name, types, ",", indent_level, ctx.options());
+
+                for (i, line) in lines.iter().enumerate() {
+                    if i > 0 {
+                        w.write_char('\n')?;
+                    }
+
+                    crate::write_indent(w, indent_level)?;
+                    w.write_str(line)?;
+                }
             }
             EnumVariant::EmptyVariant { name } => {
-                format!("{}{},", indent_string, name)
+                crate::write_indent(w, indent_level)?;
+                write!(w, "{},", name)?;
             }
-        };
+        }
+
+        return Ok(());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rust_component::RenderOptions;
 
     mod test_enum_variant {
         use super::*;
@@ -569,5 +743,22 @@ mod tests {
                 "pub(crate) enum Animals<T> {\n    Cow {\n        age: u64,\n    },\n    Dog {\n        age: u64,\n        weight: u64,\n    },\n}\n".to_string()
             );
         }
+
+        #[test]
+        fn test_wrapped_value_variant() {
+            let e = RustEnum::new("Animals")
+                .with_variant(
+                    EnumVariant::build("Cow")
+                        .with_value("String")
+                        .with_value("u64")
+                        .build(),
+                )
+                .with_variant(EnumVariant::build("Dog").with_value("u64").build());
+
+            assert_eq!(
+                e.to_rust_string_with(0, &RenderOptions::new().with_max_width(20)),
+                "enum Animals {\n    Cow(\n        String,\n        u64,\n    ),\n    Dog(u64),\n}\n"
+            );
+        }
     }
 }
@@ -1,7 +1,19 @@
-use crate::rust_component::RustComponent;
+use crate::rust_component::{RenderContext, RenderOptions, RustComponent};
+use crate::source_map::{RecordingWriter, SourceMap, SourceMapRecorder};
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
 
 /// Represents a file of rust code
 ///
+/// # Layout
+/// The file is generated as the docstring, the inner attributes, the imports, the top string, each
+/// component and the bottom string, in that order. Empty parts are left out, the remaining parts
+/// are separated by a single empty line and the file ends with a single new line.
+/// [`RustFile::into_rust_code`], [`RustFile::to_rust_code`], [`RustFile::write_rust_code`] and
+/// [`RustFile::write_to`] all generate the same code.
+///
 /// # Construction
 /// Create a new instance using the builder syntax and generates the corresponding code as a string.
 /// ```
@@ -14,12 +26,50 @@ use crate::rust_component::RustComponent;
 ///                     .with_component(RustStruct::new("MyStruct").into())
 ///                     .into_rust_code();
 /// ```
+///
+/// # Serialization
+/// With the `serde` feature the file and every component implement `Serialize` and
+/// `Deserialize`, so a tree can be cached between runs or produced by other tools and rendered.
+/// Only names, the types of fields and variables and the kind of variables are required, every
+/// other key defaults to empty, `false` or private.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use rmod_gen::RustFile;
+///
+/// let file: RustFile = serde_json::from_str(
+///     r#"{"components": [{"Struct": {"name": "Cow", "fields": [{"name": "age", "type": "u64"}]}}]}"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(file.into_rust_code(), "struct Cow {\n    age: u64,\n}\n");
+/// # }
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct RustFile {
+    #[cfg_attr(feature = "serde", serde(rename = "components"))]
     root_components: Vec<RustComponent>,
     imports: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "docstring"))]
     file_docstring: String,
+    inner_attributes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "top_string"))]
     top_misc: String,
+    #[cfg_attr(feature = "serde", serde(rename = "bottom_string"))]
     bottom_misc: String,
+    render_options: RenderOptions,
+}
+
+impl Default for RustFile {
+    fn default() -> Self {
+        return Self::new();
+    }
 }
 
 impl RustFile {
@@ -29,8 +79,10 @@ impl RustFile {
             root_components: Vec::new(),
             imports: Vec::new(),
             file_docstring: String::new(),
+            inner_attributes: Vec::new(),
             top_misc: String::new(),
             bottom_misc: String::new(),
+            render_options: RenderOptions::new(),
         };
     }
 
@@ -80,6 +132,24 @@ impl RustFile {
         return self;
     }
 
+    /// Adds an inner attribute, e.g. `#![allow(dead_code)]`, written after the docstring and
+    /// before the imports.
+    ///
+    /// ```
+    /// use rmod_gen::RustFile;
+    ///
+    /// let file = RustFile::new()
+    ///     .with_import("use std::fmt")
+    ///     .with_inner_attribute("#![allow(dead_code)]");
+    ///
+    /// assert_eq!(file.into_rust_code(), "#![allow(dead_code)]\n\nuse std::fmt;\n");
+    /// ```
+    pub fn with_inner_attribute(mut self, attribute: &str) -> Self {
+        self.push_inner_attribute(attribute);
+
+        return self;
+    }
+
     /// Specifies a miscellaneous string to be included at the top of the file.
     pub fn with_top_string(mut self, s: &str) -> Self {
         self.top_misc = s.to_string();
@@ -94,87 +164,282 @@ impl RustFile {
         return self;
     }
 
+    /// Specifies the options used when generating the code, e.g. the maximum width of a line.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustMethod};
+    /// use rmod_gen::rust_component::RenderOptions;
+    ///
+    /// let code = RustFile::new()
+    ///     .with_render_options(RenderOptions::new().with_max_width(20))
+    ///     .with_component(RustMethod::new("mix").with_argument("a: u8").with_argument("b: u8").into())
+    ///     .into_rust_code();
+    ///
+    /// assert_eq!(code, "fn mix(\n    a: u8,\n    b: u8,\n) {\n}\n");
+    /// ```
+    pub fn with_render_options(mut self, options: RenderOptions) -> Self {
+        self.set_render_options(options);
+
+        return self;
+    }
+
+    /// Specifies the options used when generating the code.
+    pub fn set_render_options(&mut self, options: RenderOptions) {
+        self.render_options = options;
+    }
+
     /// Appends a component to the file.
     pub fn push_component(&mut self, component: RustComponent) {
         self.root_components.push(component);
     }
 
+    /// Appends an inner attribute to the file.
+    pub fn push_inner_attribute(&mut self, attribute: &str) {
+        self.inner_attributes.push(attribute.to_string());
+    }
+
     /// Appends an import to the imports list.
     pub fn push_import(&mut self, import: &str) {
         self.imports.push(import.to_string());
     }
 
+    /// Takes the docstring and the top and bottom strings of `other` where this file has none.
+    pub(crate) fn fill_text_from(&mut self, other: &RustFile) {
+        for (ours, theirs) in [
+            (&mut self.file_docstring, &other.file_docstring),
+            (&mut self.top_misc, &other.top_misc),
+            (&mut self.bottom_misc, &other.bottom_misc),
+        ] {
+            if ours.is_empty() {
+                ours.clone_from(theirs);
+            }
+        }
+    }
+
+    /// Removes the first root component called `name`, returning it if it existed.
+    pub fn remove_component(&mut self, name: &str) -> Option<RustComponent> {
+        let index = self
+            .root_components
+            .iter()
+            .position(|c| c.name() == Some(name))?;
+
+        return Some(self.root_components.remove(index));
+    }
+
+    /// The components at the root of the file.
+    pub fn components(&self) -> &Vec<RustComponent> {
+        return &self.root_components;
+    }
+
+    /// The components at the root of the file, which can be edited, reordered or removed in
+    /// place.
+    pub fn components_mut(&mut self) -> &mut Vec<RustComponent> {
+        return &mut self.root_components;
+    }
+
+    /// The first root component called `name`, see [`RustComponent::name`].
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustStruct};
+    /// use rmod_gen::rust_component::{Field, RustComponent};
+    ///
+    /// let mut file = RustFile::new().with_component(RustStruct::new("Cow").into());
+    ///
+    /// if let Some(RustComponent::Struct(cow)) = file.find_component_mut("Cow") {
+    ///     cow.push_field(Field::private("age", "u64"));
+    /// }
+    ///
+    /// assert_eq!(file.into_rust_code(), "struct Cow {\n    age: u64,\n}\n");
+    /// ```
+    pub fn find_component(&self, name: &str) -> Option<&RustComponent> {
+        return self.root_components.iter().find(|c| c.name() == Some(name));
+    }
+
+    pub fn find_component_mut(&mut self, name: &str) -> Option<&mut RustComponent> {
+        return self
+            .root_components
+            .iter_mut()
+            .find(|c| c.name() == Some(name));
+    }
+
+    pub fn inner_attributes(&self) -> &Vec<String> {
+        return &self.inner_attributes;
+    }
+
+    pub fn imports(&self) -> &Vec<String> {
+        return &self.imports;
+    }
+
+    pub fn render_options(&self) -> &RenderOptions {
+        return &self.render_options;
+    }
+
     /// Converts the file into a string containing the contents for this file as Rust code.
     pub fn into_rust_code(self) -> String {
-        let mut lines = Vec::new();
+        let mut code = String::new();
+
+        self.write_rust_code(&mut code)
+            .expect("writing to a String does not fail");
+
+        return code;
+    }
+
+    /// Writes the contents of this file as Rust code, the same code as
+    /// [`RustFile::into_rust_code`], to `w` without building the whole file in memory.
+    pub fn write_rust_code(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        return self.write_code(w, &RenderContext::new(0, self.render_options));
+    }
+
+    /// Converts the file into Rust code, as in [`RustFile::into_rust_code`], along with a map of
+    /// the lines generated by each component.
+    pub fn into_rust_code_with_source_map(self) -> (String, SourceMap) {
+        let mut code = String::new();
+
+        let source_map = self
+            .write_rust_code_with_source_map(&mut code)
+            .expect("writing to a String does not fail");
+
+        return (code, source_map);
+    }
+
+    /// Writes the contents of this file as Rust code to `w`, as in
+    /// [`RustFile::write_rust_code`], returning a map of the lines generated by each component.
+    pub fn write_rust_code_with_source_map(
+        &self,
+        w: &mut dyn fmt::Write,
+    ) -> Result<SourceMap, fmt::Error> {
+        let recorder = RefCell::new(SourceMapRecorder::new());
+        let ctx = RenderContext::new(0, self.render_options).with_source_map(&recorder);
+
+        self.write_code(&mut RecordingWriter::new(w, &recorder), &ctx)?;
+
+        return Ok(recorder.into_inner().finish());
+    }
+
+    fn write_code(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let mut sections = Sections::new();
 
         if !self.file_docstring.is_empty() {
-            lines.push(self.file_docstring);
-            lines.push(String::new()); // Empty line
+            sections.write_text(w, &self.file_docstring)?;
+        }
+
+        if !self.inner_attributes.is_empty() {
+            sections.begin(w)?;
+
+            for attribute in &self.inner_attributes {
+                w.write_str(attribute)?;
+                w.write_char('\n')?;
+            }
         }
 
         if !self.imports.is_empty() {
-            lines.extend(
-                self.imports
-                    .iter()
-                    .map(|s| [s.clone(), String::from(";")].join("")),
-            );
+            sections.begin(w)?;
 
-            lines.push(String::new()); // Empty line
+            for import in &self.imports {
+                w.write_str(import)?;
+                w.write_str(";\n")?;
+            }
         }
 
         if !self.top_misc.is_empty() {
-            lines.push(self.top_misc);
-            lines.push(String::new()); // Empty line
+            sections.write_text(w, &self.top_misc)?;
         }
 
-        let has_components = self.root_components.is_empty();
-
-        for component in self.root_components {
-            lines.push(component.to_rust_string(0));
+        for component in &self.root_components {
+            sections.begin(w)?;
+            component.write_rust(&mut crate::TrimTrailingNewlines::new(w), ctx)?;
+            w.write_char('\n')?;
         }
 
-        if !has_components {
-            lines.push(String::new()); // Empty line
+        if !self.bottom_misc.is_empty() {
+            sections.write_text(w, &self.bottom_misc)?;
         }
 
-        if !self.bottom_misc.is_empty() {
-            lines.push(self.bottom_misc);
-            lines.push(String::new()); // Empty line
+        return Ok(());
+    }
+
+    /// Writes the contents of this file as Rust code, the same code as
+    /// [`RustFile::into_rust_code`], to `writer`. The output is buffered.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustStruct};
+    ///
+    /// let mut code = Vec::new();
+    /// RustFile::new().with_component(RustStruct::new("Cow").into()).write_to(&mut code).unwrap();
+    ///
+    /// assert_eq!(code, b"struct Cow {\n}\n");
+    /// ```
+    pub fn write_to<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: io::BufWriter::new(writer),
+            error: None,
+        };
+
+        if self.write_rust_code(&mut adapter).is_err() {
+            return Err(adapter
+                .error
+                .unwrap_or_else(|| io::Error::other("could not format the file")));
         }
 
-        return lines.join("\n");
+        return adapter.inner.flush();
     }
 
-    /// Generates a representation of this file as rust code.
+    /// Generates a representation of this file as rust code, the same code as
+    /// [`RustFile::into_rust_code`].
     pub fn to_rust_code(&self) -> String {
-        let mut lines = vec![self.file_docstring.clone()];
+        let mut code = String::new();
 
-        lines.push(String::new()); // Empty line
+        self.write_rust_code(&mut code)
+            .expect("writing to a String does not fail");
 
-        lines.extend(
-            self.imports
-                .iter()
-                .map(|s| [s.clone(), String::from(";")].join("")),
-        );
+        return code;
+    }
+}
 
-        lines.push(String::new()); // Empty line
+/// Separates the sections of a file, i.e. the docstring, inner attributes, imports, top string,
+/// each component and the bottom string, with an empty line. Every section ends with a single new
+/// line.
+struct Sections {
+    first: bool,
+}
 
-        if !self.top_misc.is_empty() {
-            lines.push(self.top_misc.clone());
-            lines.push(String::new()); // Empty line
-        }
+impl Sections {
+    fn new() -> Self {
+        return Self { first: true };
+    }
 
-        for component in &self.root_components {
-            lines.push(component.to_rust_string(0));
-            lines.push(String::new()); // Empty line
-        }
+    /// Starts a section, writing the empty line separating it from the previous section.
+    fn begin(&mut self, w: &mut dyn fmt::Write) -> fmt::Result {
+        if self.first {
+            self.first = false;
 
-        if !self.bottom_misc.is_empty() {
-            lines.push(self.bottom_misc.clone());
-            lines.push(String::new()); // Empty line
+            return Ok(());
         }
 
-        return lines.join("\n");
+        return w.write_char('\n');
+    }
+
+    /// Writes a section of text, ignoring any new lines it ends with.
+    fn write_text(&mut self, w: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        self.begin(w)?;
+        w.write_str(text.trim_end_matches('\n'))?;
+
+        return w.write_char('\n');
+    }
+}
+
+/// Adapts an [`io::Write`] to a [`fmt::Write`], keeping the error which caused writing to fail.
+struct IoAdapter<W: io::Write> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        return self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+
+            fmt::Error
+        });
     }
 }
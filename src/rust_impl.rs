@@ -1,4 +1,9 @@
-use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage};
+use crate::rust_component::{RenderContext, RustComponent, RustComponentTrait, RustTemplateUsage};
+use crate::source_map::{ComponentGuard, Origin};
+use crate::wrap::Header;
+use crate::{RustMethod, RustStruct, RustTrait};
+
+use std::fmt;
 
 /// Specifies a Rust impl block.
 ///
@@ -24,19 +29,30 @@ use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage
 ///             .with_component(RustMethod::new("fill").into())
 ///             .to_rust_string(0);
 ///
-/// let cmp = "impl Container for Carton<B> {\n    fn pour() {\n    }\n\n    fn fill() {\n    }\n\n}\n".to_string();
+/// let cmp = "impl Container for Carton<B> {\n    fn pour() {\n    }\n\n    fn fill() {\n    }\n}\n".to_string();
 ///
 /// assert_eq!(s, cmp);
 /// ```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustImplementation {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     components: Vec<RustComponent>,
+    #[cfg_attr(feature = "serde", serde(default))]
     impl_lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     target_lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     impl_templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     target_templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     extra: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    where_predicates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustImplementation {
@@ -49,6 +65,8 @@ impl RustImplementation {
             impl_templates: Vec::new(),
             target_templates: Vec::new(),
             extra: String::new(),
+            where_predicates: Vec::new(),
+            origin: Origin::default(),
         };
     }
 
@@ -61,9 +79,162 @@ impl RustImplementation {
             impl_templates: Vec::new(),
             target_templates: Vec::new(),
             extra: String::new(),
+            where_predicates: Vec::new(),
+            origin: Origin::default(),
         };
     }
 
+    /// Creates an implementation of `rust_trait` for `target`. Every required item of the trait is
+    /// included with the same signature, `Self` is replaced with `target` and each required method
+    /// is given a `todo!()` body ready to be filled in. Associated consts without a default are set
+    /// to `todo!()` and associated types without a default are set to `()`, with a `TODO` comment
+    /// naming their bounds if they have any, since `()` rarely satisfies them.
+    ///
+    /// The templates and lifetimes of the trait become templates and lifetimes of the impl.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod, RustTrait};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_trait = RustTrait::new("Merge")
+    ///     .with_template("T")
+    ///     .with_component(RustMethod::new("merge").with_argument("self").with_argument("other: T").with_return_type("Self").without_body().into());
+    ///
+    /// let s = RustImplementation::for_trait(&rust_trait, "Config").to_rust_string(0);
+    ///
+    /// assert_eq!(s, "impl<T> Merge<T> for Config {\n    fn merge(self, other: T) -> Config {\n        todo!()\n    }\n}\n");
+    /// ```
+    pub fn for_trait(rust_trait: &RustTrait, target: &str) -> Self {
+        return Self::for_trait_with_generics(rust_trait, target, &[]);
+    }
+
+    /// Creates an implementation of `rust_trait` for `target` like
+    /// [`RustImplementation::for_trait`], substituting the templates of the trait, in order, with
+    /// the concrete types in `generics`. Templates without a corresponding concrete type remain
+    /// templates of the impl.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod, RustTrait};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_trait = RustTrait::new("Merge")
+    ///     .with_template("T")
+    ///     .with_component(RustMethod::new("merge").with_argument("self").with_argument("other: T").with_return_type("Self").without_body().into());
+    ///
+    /// let s = RustImplementation::for_trait_with_generics(&rust_trait, "Config", &["u32"]).to_rust_string(0);
+    ///
+    /// assert_eq!(s, "impl Merge<u32> for Config {\n    fn merge(self, other: u32) -> Config {\n        todo!()\n    }\n}\n");
+    /// ```
+    pub fn for_trait_with_generics(
+        rust_trait: &RustTrait,
+        target: &str,
+        generics: &[&str],
+    ) -> Self {
+        let (mut implementation, substitutions) = Self::trait_header(rust_trait, target, generics);
+
+        for item in rust_trait.items() {
+            let component = match item.skeleton() {
+                Some(RustComponent::Method(mut method)) => {
+                    for substitution in &substitutions {
+                        method.substitute_signature(substitution);
+                    }
+
+                    method.into()
+                }
+                Some(RustComponent::Variable(mut variable)) => {
+                    for substitution in &substitutions {
+                        let tp = substitution.apply(variable.tp());
+
+                        variable.set_type(&tp);
+                    }
+
+                    variable.into()
+                }
+                Some(component) => component,
+                None => continue,
+            };
+
+            implementation.push_component(component);
+        }
+
+        return implementation;
+    }
+
+    /// Creates an empty implementation of `rust_trait` for `target`, returning it alongside the
+    /// substitutions that should be applied to the signatures of the trait's methods.
+    pub(crate) fn trait_header(
+        rust_trait: &RustTrait,
+        target: &str,
+        generics: &[&str],
+    ) -> (Self, Vec<Substitution>) {
+        let mut trait_arguments = Vec::new();
+        let mut impl_templates = Vec::new();
+        // `Self::Output` still refers to the associated type within the implementation.
+        let mut substitutions = vec![Substitution::new("Self", target, "Self")];
+
+        for (i, template) in rust_trait.templates().iter().enumerate() {
+            let template_name = crate::rust_component::template_name(template);
+
+            match generics.get(i) {
+                Some(concrete) => {
+                    trait_arguments.push(concrete.to_string());
+                    substitutions.push(Substitution::for_template(template, concrete));
+                }
+                None => {
+                    trait_arguments.push(template_name.to_string());
+                    impl_templates.push(template.clone());
+                }
+            }
+        }
+
+        let mut implementation = Self::new_for(
+            &format!(
+                "{}{}",
+                rust_trait.name(),
+                Self::create_template_string(&trait_arguments, rust_trait.lifetimes())
+            ),
+            target,
+        );
+
+        implementation.impl_lifetimes = rust_trait.lifetimes().clone();
+        implementation.impl_templates = impl_templates;
+
+        return (implementation, substitutions);
+    }
+
+    /// Creates an empty inherent implementation for `rust_struct`, carrying over its templates and
+    /// lifetimes.
+    pub(crate) fn for_struct(rust_struct: &RustStruct) -> Self {
+        let mut implementation = Self::new(rust_struct.name());
+
+        for lifetime in rust_struct.lifetimes() {
+            implementation.push_lifetime(lifetime);
+        }
+
+        for template in rust_struct.templates() {
+            implementation.push_impl_template(template);
+            implementation.push_target_template(crate::rust_component::template_name(template));
+        }
+
+        return implementation;
+    }
+
+    /// Creates an empty implementation of `rust_trait` for `rust_struct`, carrying over the
+    /// templates and lifetimes of both.
+    pub(crate) fn trait_for_struct(rust_trait: &RustTrait, rust_struct: &RustStruct) -> Self {
+        let (mut implementation, _) =
+            Self::trait_header(rust_trait, &rust_struct.type_string(), &[]);
+
+        implementation
+            .impl_lifetimes
+            .extend(rust_struct.lifetimes().iter().cloned());
+        implementation
+            .impl_templates
+            .extend(rust_struct.templates().iter().cloned());
+
+        return implementation;
+    }
+
     pub fn with_component(mut self, component: RustComponent) -> Self {
         self.push_component(component);
 
@@ -128,6 +299,24 @@ impl RustImplementation {
         return self;
     }
 
+    /// Adds a predicate to the where clause of the implementation.
+    ///
+    /// ```
+    /// use rmod_gen::RustImplementation;
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_impl = RustImplementation::new_for("Debug", "Wrapper")
+    ///     .with_template("T")
+    ///     .with_where_predicate("T: Debug");
+    ///
+    /// assert_eq!(rust_impl.to_rust_string(0), "impl<T> Debug for Wrapper<T> where T: Debug {\n}\n");
+    /// ```
+    pub fn with_where_predicate(mut self, predicate: &str) -> Self {
+        self.push_where_predicate(predicate);
+
+        return self;
+    }
+
     pub fn push_component(&mut self, component: RustComponent) {
         self.components.push(component);
     }
@@ -179,6 +368,143 @@ impl RustImplementation {
     pub fn set_extra(&mut self, extra: &str) {
         self.extra = extra.to_string();
     }
+
+    pub fn push_where_predicate(&mut self, predicate: &str) {
+        self.where_predicates.push(predicate.to_string());
+    }
+
+    /// Returns the names of the required items of `rust_trait` that are not defined in this
+    /// implementation. Methods, associated constants and associated types (given as text starting
+    /// with `type`) are considered.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod, RustTrait};
+    ///
+    /// let rust_trait = RustTrait::new("Shape")
+    ///     .with_component(RustMethod::new("area").without_body().into())
+    ///     .with_component(RustMethod::new("sides").without_body().into());
+    ///
+    /// let implementation = RustImplementation::new_for("Shape", "Square")
+    ///     .with_component(RustMethod::new("area").into());
+    ///
+    /// assert_eq!(implementation.missing_trait_items(&rust_trait), vec!["sides".to_string()]);
+    /// ```
+    pub fn missing_trait_items(&self, rust_trait: &RustTrait) -> Vec<String> {
+        let defined: Vec<&str> = self
+            .components
+            .iter()
+            .filter_map(|component| match component {
+                RustComponent::Method(method) => Some(method.name()),
+                RustComponent::Variable(variable) if variable.is_const() => Some(variable.name()),
+                RustComponent::Text(text) => text.associated_type_name(),
+                _ => None,
+            })
+            .collect();
+
+        return rust_trait
+            .items()
+            .iter()
+            .filter(|item| item.is_required())
+            .filter_map(|item| item.name())
+            .filter(|name| !defined.contains(name))
+            .map(str::to_string)
+            .collect();
+    }
+
+    /// Returns true if this implementation defines every required item of `rust_trait`.
+    pub fn covers_trait(&self, rust_trait: &RustTrait) -> bool {
+        return self.missing_trait_items(rust_trait).is_empty();
+    }
+
+    /// Records where the implementation was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the implementation was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Removes the first component called `name`, returning it if it existed.
+    pub fn remove_component(&mut self, name: &str) -> Option<RustComponent> {
+        let index = self
+            .components
+            .iter()
+            .position(|c| c.name() == Some(name))?;
+
+        return Some(self.components.remove(index));
+    }
+
+    /// The name of the implementation, e.g. `Foo` or `Display for Foo`.
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn extra(&self) -> &str {
+        return &self.extra;
+    }
+
+    /// The predicates of the where clause, e.g. `T: Debug`.
+    pub fn where_predicates(&self) -> &Vec<String> {
+        return &self.where_predicates;
+    }
+
+    pub fn components(&self) -> &Vec<RustComponent> {
+        return &self.components;
+    }
+
+    /// The components of the implementation, which can be edited, reordered or removed in place.
+    pub fn components_mut(&mut self) -> &mut Vec<RustComponent> {
+        return &mut self.components;
+    }
+
+    /// The first component called `name`, see [`RustComponent::name`].
+    pub fn find_component(&self, name: &str) -> Option<&RustComponent> {
+        return self.components.iter().find(|c| c.name() == Some(name));
+    }
+
+    pub fn find_component_mut(&mut self, name: &str) -> Option<&mut RustComponent> {
+        return self.components.iter_mut().find(|c| c.name() == Some(name));
+    }
+
+    /// The method called `name`.
+    ///
+    /// ```
+    /// use rmod_gen::{RustImplementation, RustMethod};
+    ///
+    /// let mut implementation = RustImplementation::new("Cow")
+    ///     .with_component(RustMethod::new("moo").into());
+    ///
+    /// implementation.find_method_mut("moo").unwrap().set_body("println!(\"Moo\");");
+    ///
+    /// assert_eq!(implementation.find_method("moo").unwrap().body(), "println!(\"Moo\");");
+    /// ```
+    pub fn find_method(&self, name: &str) -> Option<&RustMethod> {
+        return self.components.iter().find_map(|c| match c {
+            RustComponent::Method(method) if method.name() == name => Some(method),
+            _ => None,
+        });
+    }
+
+    pub fn find_method_mut(&mut self, name: &str) -> Option<&mut RustMethod> {
+        return self.components.iter_mut().find_map(|c| match c {
+            RustComponent::Method(method) if method.name() == name => Some(method),
+            _ => None,
+        });
+    }
+
+    /// Where the implementation was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The implementation as part of a path in a source map, e.g. `impl Debug for Foo`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("impl {}", self.name);
+    }
 }
 
 impl Into<RustComponent> for RustImplementation {
@@ -187,43 +513,104 @@ impl Into<RustComponent> for RustImplementation {
     }
 }
 
+/// Replaces a template of a trait, or `Self`, with a concrete type in the signatures of an
+/// implementation.
+pub(crate) struct Substitution {
+    from: String,
+    to: String,
+    /// Replaces `from` where it starts a path, e.g. `<u32 as Iterator>` for `T::Item`, as
+    /// associated items of a concrete type must name their trait.
+    path_root: String,
+}
+
+impl Substitution {
+    fn new(from: &str, to: &str, path_root: &str) -> Self {
+        return Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            path_root: path_root.to_string(),
+        };
+    }
+
+    /// Substitutes the template, e.g. `T: Iterator`, with `concrete`. Paths starting with the
+    /// template are qualified with its first trait bound.
+    fn for_template(template: &str, concrete: &str) -> Self {
+        let bound = template.split_once(':').and_then(|(_, bounds)| {
+            bounds
+                .split('+')
+                .map(str::trim)
+                .find(|b| !b.is_empty() && !b.starts_with('\'') && !b.starts_with('?'))
+        });
+        let path_root = match bound {
+            Some(bound) => format!("<{} as {}>", concrete, bound),
+            None => format!("<{}>", concrete),
+        };
+
+        return Self::new(
+            crate::rust_component::template_name(template),
+            concrete,
+            &path_root,
+        );
+    }
+
+    pub(crate) fn apply(&self, s: &str) -> String {
+        let s = crate::rust_component::replace_identifier(s, &self.from, &self.to);
+
+        return crate::rust_component::replace_path_root(&s, &self.from, &self.path_root);
+    }
+}
+
 impl RustTemplateUsage for RustImplementation {}
 
 impl RustComponentTrait for RustImplementation {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let mut lines = Vec::new();
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
 
-        let base_indent_level = crate::indent_string(indent_level);
+        let indent_level = ctx.indent_level();
 
-        let definition_line = format!(
-            "impl{} {}{}",
-            Self::create_template_string(&self.impl_templates, &self.impl_lifetimes),
-            self.name,
-            Self::create_template_string(&self.target_templates, &self.target_lifetimes)
-        );
+        let prefix = if self.extra.is_empty() {
+            String::from("impl")
+        } else {
+            format!("{} impl", self.extra)
+        };
 
-        if self.extra.is_empty() {
-            lines.push(format!("{}{} {{", &base_indent_level, definition_line));
+        let where_clause = if self.where_predicates.is_empty() {
+            String::new()
         } else {
-            lines.push(format!(
-                "{}{} {} {{",
-                &base_indent_level, &self.extra, definition_line
-            ));
-        }
+            format!("where {}", self.where_predicates.join(", "))
+        };
 
-        for component in &self.components {
-            lines.push(component.to_rust_string(indent_level + 1));
+        let header = Header::new(&prefix, " {")
+            .with_generics(Self::create_template_items(
+                &self.impl_templates,
+                &self.impl_lifetimes,
+            ))
+            .with_suffix(&format!(
+                " {}{}",
+                self.name,
+                Self::create_template_string(&self.target_templates, &self.target_lifetimes)
+            ))
+            .with_extra(&where_clause);
+
+        for line in header.lines(indent_level, ctx.options()) {
+            crate::write_indent(w, indent_level)?;
+            w.write_str(&line)?;
+            w.write_char('\n')?;
         }
 
-        lines.push(format!("{}}}\n", base_indent_level));
+        crate::write_components(w, &self.components, &ctx.nested())?;
+
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
 
-        return lines.join("\n");
+        return Ok(());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{RustMethod, RustText, TraitItem};
 
     #[test]
     fn test_impl_empty() {
@@ -261,6 +648,91 @@ mod tests {
         assert_eq!(s, cmp);
     }
 
+    #[test]
+    fn test_for_trait_generics_and_lifetimes() {
+        let rust_trait = RustTrait::new("Parser")
+            .with_lifetime("a")
+            .with_template("T")
+            .with_template("E: Debug")
+            .with_component(
+                RustMethod::new("parse")
+                    .with_argument("input: &'a str")
+                    .with_return_type("Result<T, E>")
+                    .without_body()
+                    .into(),
+            );
+
+        let s = RustImplementation::for_trait_with_generics(&rust_trait, "Json", &["Value"])
+            .to_rust_string(0);
+
+        assert_eq!(s, "impl<'a, E: Debug> Parser<'a, Value, E> for Json {\n    fn parse(input: &'a str) -> Result<Value, E> {\n        todo!()\n    }\n}\n");
+    }
+
+    #[test]
+    fn test_for_trait_generic_const() {
+        let rust_trait = RustTrait::new("Identified")
+            .with_template("T")
+            .with_item(TraitItem::new_const("ID", "T", None));
+
+        let s = RustImplementation::for_trait_with_generics(&rust_trait, "User", &["u64"])
+            .to_rust_string(0);
+
+        assert_eq!(
+            s,
+            "impl Identified<u64> for User {\n    const ID: u64 = todo!();\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_for_trait_generic_projection() {
+        let rust_trait = RustTrait::new("Drain")
+            .with_template("I: Iterator")
+            .with_item(TraitItem::new_type(
+                "Output",
+                vec![String::from("Clone")],
+                None,
+            ))
+            .with_item(TraitItem::new_required(
+                RustMethod::new("first")
+                    .with_argument("&self")
+                    .with_argument("items: I")
+                    .with_return_type("Option<I::Item>"),
+            ));
+
+        let s = RustImplementation::for_trait_with_generics(
+            &rust_trait,
+            "Queue",
+            &["std::vec::IntoIter<u8>"],
+        )
+        .to_rust_string(0);
+
+        assert_eq!(
+            s,
+            "impl Drain<std::vec::IntoIter<u8>> for Queue {\n    type Output = (); // TODO: a type implementing Clone\n\n    fn first(&self, items: std::vec::IntoIter<u8>) -> Option<<std::vec::IntoIter<u8> as Iterator>::Item> {\n        todo!()\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_covers_trait() {
+        let rust_trait = RustTrait::new("Shape")
+            .with_item(TraitItem::new_type("Unit", vec![], None))
+            .with_item(TraitItem::new_const("SIDES", "u32", None))
+            .with_item(TraitItem::new_required(RustMethod::new("area")))
+            .with_item(TraitItem::new_provided(RustMethod::new("name")));
+
+        let skeleton = RustImplementation::for_trait(&rust_trait, "Square");
+        assert!(skeleton.covers_trait(&rust_trait));
+
+        let partial = RustImplementation::new_for("Shape", "Square")
+            .with_component(RustMethod::new("area").into())
+            .with_component(RustText::new("type Unit = f32;").into());
+
+        assert_eq!(
+            partial.missing_trait_items(&rust_trait),
+            vec!["SIDES".to_string()]
+        );
+    }
+
     #[test]
     fn test_impl_for_target_template() {
         let s = RustImplementation::new_for("Container", "Carton")
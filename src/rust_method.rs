@@ -1,4 +1,11 @@
-use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage, Visibility};
+use crate::rust_component::{
+    RenderContext, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
+};
+use crate::rust_impl::Substitution;
+use crate::source_map::{ComponentGuard, Origin};
+use crate::wrap::Header;
+
+use std::fmt;
 
 /// Represents a function or method in Rust.
 ///
@@ -29,18 +36,33 @@ use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage
 /// assert_eq!(method.to_rust_string(0), "pub unsafe fn create_cow<'a, T>(name: &str, age: u64) -> Cow {\n    let cow = Cow::new();\n    return cow;\n}\n");
 ///```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustMethod {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     fn_type: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
     arguments: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     return_type: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     body: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     extra: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     cfg: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    docs: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     without_body: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustMethod {
@@ -57,7 +79,9 @@ impl RustMethod {
             lifetimes: Vec::new(),
             extra: String::new(),
             cfg: String::new(),
+            docs: String::new(),
             without_body: false,
+            origin: Origin::default(),
         };
     }
 
@@ -109,6 +133,22 @@ impl RustMethod {
         return self;
     }
 
+    /// Documents the method with the specified text. Text should not contain any docstring lines.
+    ///
+    /// ```
+    /// use rmod_gen::RustMethod;
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let method = RustMethod::new("my_method").with_docs("Does something.\nTwice.").to_rust_string(1);
+    ///
+    /// assert_eq!(method, "    /// Does something.\n    /// Twice.\n    fn my_method() {\n    }\n");
+    /// ```
+    pub fn with_docs(mut self, docs: &str) -> Self {
+        self.set_docs(docs);
+
+        return self;
+    }
+
     /// Sets the visibility for this method.
     pub fn with_visibility(mut self, visibility: Visibility) -> Self {
         self.set_visibility(visibility);
@@ -192,6 +232,11 @@ impl RustMethod {
         self.fn_type = tp.to_string();
     }
 
+    /// Documents the method with the specified text. Text should not contain any docstring lines.
+    pub fn set_docs(&mut self, docs: &str) {
+        self.docs = docs.to_string();
+    }
+
     /// Sets the visibility for this method.
     pub fn set_visibility(&mut self, visibility: Visibility) {
         self.visibility = visibility;
@@ -278,6 +323,108 @@ impl RustMethod {
     pub fn set_with_body(&mut self, body: bool) {
         self.without_body = !body;
     }
+
+    /// Renames the method.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Returns true if the method is generated with a body rather than a semicolon.
+    pub fn has_body(&self) -> bool {
+        return !self.without_body;
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn fn_type(&self) -> &str {
+        return &self.fn_type;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn arguments(&self) -> &Vec<String> {
+        return &self.arguments;
+    }
+
+    /// The arguments of the method, e.g. `&self` or `name: &str`, which can be edited in place.
+    pub fn arguments_mut(&mut self) -> &mut Vec<String> {
+        return &mut self.arguments;
+    }
+
+    pub fn return_type(&self) -> &str {
+        return &self.return_type;
+    }
+
+    pub fn body(&self) -> &str {
+        return &self.body;
+    }
+
+    pub fn templates(&self) -> &Vec<String> {
+        return &self.templates;
+    }
+
+    pub fn lifetimes(&self) -> &Vec<String> {
+        return &self.lifetimes;
+    }
+
+    pub fn extra(&self) -> &str {
+        return &self.extra;
+    }
+
+    pub fn cfg(&self) -> &str {
+        return &self.cfg;
+    }
+
+    pub fn docs(&self) -> &str {
+        return &self.docs;
+    }
+
+    /// The method as a declaration without a body or attributes, e.g. `pub fn new() -> Self;`.
+    pub(crate) fn signature(&self) -> String {
+        let mut method = self.clone();
+
+        method.set_docs("");
+        method.set_cfg("");
+        method.set_with_body(false);
+
+        return method.to_rust_string(0).trim().to_string();
+    }
+
+    /// Applies a substitution to the arguments, return type and extra information of this method.
+    pub(crate) fn substitute_signature(&mut self, substitution: &Substitution) {
+        for argument in self.arguments.iter_mut() {
+            *argument = substitution.apply(argument);
+        }
+
+        self.return_type = substitution.apply(&self.return_type);
+        self.extra = substitution.apply(&self.extra);
+    }
+
+    /// Records where the method was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the method was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the method was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The method as part of a path in a source map, e.g. `fn bar`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("fn {}", self.name);
+    }
 }
 
 impl Into<RustComponent> for RustMethod {
@@ -289,74 +436,98 @@ impl Into<RustComponent> for RustMethod {
 impl RustTemplateUsage for RustMethod {}
 
 impl RustComponentTrait for RustMethod {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let base_indent_string = crate::indent_string(indent_level);
-        let next_level_indent_string = crate::indent_string(indent_level + 1);
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
 
-        let mut components = vec![base_indent_string.clone()];
+        let indent_level = ctx.indent_level();
 
+        crate::write_docs(w, &self.docs, "///", indent_level)?;
         if !self.cfg.is_empty() {
-            components.push(self.cfg.clone());
-            components.push("\n".to_string());
-            components.push(base_indent_string.clone());
+            crate::write_indented(w, &self.cfg, indent_level)?;
+            w.write_char('\n')?;
         }
 
+        crate::write_indent(w, indent_level)?;
+
+        let mut prefix = String::new();
+
         if self.visibility != Visibility::Private {
-            components.push(self.visibility.to_string());
-            components.push(" ".to_string());
+            prefix.push_str(&format!("{} ", self.visibility));
         }
 
         if !self.fn_type.is_empty() {
-            components.push(self.fn_type.clone());
-            components.push(" ".to_string());
+            prefix.push_str(&format!("{} ", self.fn_type));
         }
 
-        components.push("fn ".to_string());
-
-        components.push(self.name.clone());
+        prefix.push_str(&format!("fn {}", self.name));
 
-        let templates_string = Self::create_template_string(&self.templates, &self.lifetimes);
+        let mut header = Header::new(&prefix, if self.without_body { ";" } else { " {" })
+            .with_generics(Self::create_template_items(
+                &self.templates,
+                &self.lifetimes,
+            ))
+            .with_arguments(&self.arguments)
+            .with_extra(&self.extra);
 
-        if !templates_string.is_empty() {
-            components.push(templates_string);
+        if !self.return_type.is_empty() {
+            header = header.with_suffix(&format!(" -> {}", self.return_type));
         }
 
-        components.push("(".to_string());
-        components.push(self.arguments.join(", "));
-        components.push(")".to_string());
+        for (i, line) in header.lines(indent_level, ctx.options()).iter().enumerate() {
+            if i > 0 {
+                w.write_char('\n')?;
+                crate::write_indent(w, indent_level)?;
+            }
 
-        if !self.return_type.is_empty() {
-            components.push(" -> ".to_string());
-            components.push(self.return_type.clone());
+            w.write_str(line)?;
         }
 
-        if !self.extra.is_empty() {
-            components.push(" ".to_string());
-            components.push(self.extra.clone());
-        }
+        w.write_char('\n')?;
 
         if self.without_body {
-            components.push(";\n".to_string());
-        } else {
-            components.push(" {\n".to_string());
-
-            for line in self.body.lines() {
-                components.push(next_level_indent_string.clone());
-                components.push(line.to_string());
-                components.push("\n".to_string());
+            return Ok(());
+        }
+
+        for line in self.body.lines() {
+            // Empty lines are not indented to avoid trailing whitespace.
+            if line.is_empty() {
+                w.write_char('\n')?;
+                continue;
             }
 
-            components.push(base_indent_string);
-            components.push("}\n".to_string());
+            let wrapped = if ctx.options().fits(indent_level + 1, line) {
+                None
+            } else {
+                crate::wrap::wrap_struct_literal(line)
+            };
+
+            match wrapped {
+                Some(lines) => {
+                    for line in lines {
+                        crate::write_indent(w, indent_level + 1)?;
+                        w.write_str(&line)?;
+                        w.write_char('\n')?;
+                    }
+                }
+                None => {
+                    crate::write_indent(w, indent_level + 1)?;
+                    w.write_str(line)?;
+                    w.write_char('\n')?;
+                }
+            }
         }
 
-        return components.join("");
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
+
+        return Ok(());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rust_component::RenderOptions;
 
     #[test]
     fn test_basic_method() {
@@ -441,4 +612,59 @@ mod tests {
 
         assert_eq!(method.to_rust_string(0), "pub unsafe fn create_cow<'a, T>(name: &str, age: u64) -> Cow {\n    let cow = Cow::new();\n    return cow;\n}\n");
     }
+
+    #[test]
+    fn test_method_wrapped_signature() {
+        let method = RustMethod::new("create_cow")
+            .with_template("T: Into<String>")
+            .with_visibility(Visibility::Public)
+            .with_return_type("Cow")
+            .with_argument("name: T")
+            .with_argument("age: u64")
+            .with_extra("where T: Clone")
+            .without_body();
+
+        assert_eq!(
+            method.to_rust_string_with(1, &RenderOptions::new().with_max_width(70)),
+            "    pub fn create_cow<T: Into<String>>(name: T, age: u64) -> Cow\n    where\n        T: Clone;\n"
+        );
+        assert_eq!(
+            method.to_rust_string_with(1, &RenderOptions::new().with_max_width(50)),
+            "    pub fn create_cow<T: Into<String>>(\n        name: T,\n        age: u64,\n    ) -> Cow\n    where\n        T: Clone;\n"
+        );
+    }
+
+    #[test]
+    fn test_method_wrapped_struct_literal() {
+        let method = RustMethod::new("new")
+            .with_return_type("Self")
+            .with_body("return Self { name: String::new(), age: 0 };");
+
+        assert_eq!(
+            method.to_rust_string_with(0, &RenderOptions::new().with_max_width(40)),
+            "fn new() -> Self {\n    return Self {\n        name: String::new(),\n        age: 0,\n    };\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_method_format_string_not_wrapped() {
+        let body = r#"return write!(f, "Point {x_coordinate}", x_coordinate = self.x_coordinate_value_long);"#;
+        let method = RustMethod::new("fmt").with_body(body);
+
+        assert_eq!(
+            method.to_rust_string_with(0, &RenderOptions::new().with_max_width(60)),
+            format!("fn fmt() {{\n    {}\n}}\n", body)
+        );
+    }
+
+    #[test]
+    fn test_method_block_after_constant_not_wrapped() {
+        let body = "if value >= MAX_VALUE_ALLOWED { value_that_is_way_too_large }";
+        let method = RustMethod::new("clamp").with_body(body);
+
+        assert_eq!(
+            method.to_rust_string_with(0, &RenderOptions::new().with_max_width(40)),
+            format!("fn clamp() {{\n    {}\n}}\n", body)
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use crate::rust_component::{RustComponent, RustComponentTrait, Visibility};
+use crate::rust_component::{RenderContext, RustComponent, RustComponentTrait, Visibility};
+use crate::source_map::{ComponentGuard, Origin};
+
+use std::fmt;
 
 /// Represents a module in Rust.
 ///
@@ -40,12 +43,19 @@ use crate::rust_component::{RustComponent, RustComponentTrait, Visibility};
 /// );
 /// ```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustModule {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
     imports: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     components: Vec<RustComponent>,
+    #[cfg_attr(feature = "serde", serde(default))]
     cfg_options: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustModule {
@@ -57,6 +67,7 @@ impl RustModule {
             cfg_options: String::new(),
             imports: Vec::new(),
             components: Vec::new(),
+            origin: Origin::default(),
         };
     }
 
@@ -138,6 +149,87 @@ impl RustModule {
     pub fn push_import(&mut self, import: &str) {
         self.imports.push(import.to_string());
     }
+
+    /// Records where the module was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the module was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Renames the module.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Removes the first component called `name`, returning it if it existed.
+    pub fn remove_component(&mut self, name: &str) -> Option<RustComponent> {
+        let index = self
+            .components
+            .iter()
+            .position(|c| c.name() == Some(name))?;
+
+        return Some(self.components.remove(index));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn imports(&self) -> &Vec<String> {
+        return &self.imports;
+    }
+
+    pub fn cfg(&self) -> &str {
+        return &self.cfg_options;
+    }
+
+    pub fn components(&self) -> &Vec<RustComponent> {
+        return &self.components;
+    }
+
+    /// The components of the module, which can be edited, reordered or removed in place.
+    pub fn components_mut(&mut self) -> &mut Vec<RustComponent> {
+        return &mut self.components;
+    }
+
+    /// The first component called `name`, see [`RustComponent::name`].
+    ///
+    /// ```
+    /// use rmod_gen::{RustModule, RustStruct};
+    /// use rmod_gen::rust_component::RustComponent;
+    ///
+    /// let module = RustModule::new("api").with_component(RustStruct::new("Cow").into());
+    ///
+    /// assert!(matches!(module.find_component("Cow"), Some(RustComponent::Struct(_))));
+    /// assert!(module.find_component("Pig").is_none());
+    /// ```
+    pub fn find_component(&self, name: &str) -> Option<&RustComponent> {
+        return self.components.iter().find(|c| c.name() == Some(name));
+    }
+
+    pub fn find_component_mut(&mut self, name: &str) -> Option<&mut RustComponent> {
+        return self.components.iter_mut().find(|c| c.name() == Some(name));
+    }
+
+    /// Where the module was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The module as part of a path in a source map, e.g. `mod api`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("mod {}", self.name);
+    }
 }
 
 impl Into<RustComponent> for RustModule {
@@ -147,43 +239,39 @@ impl Into<RustComponent> for RustModule {
 }
 
 impl RustComponentTrait for RustModule {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let mut contents = Vec::new();
-        let indent_string = crate::indent_string(indent_level);
-        let import_indent_string = crate::indent_string(indent_level + 1);
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        let indent_level = ctx.indent_level();
 
         if !self.cfg_options.is_empty() {
-            contents.push(format!("{}\n", self.cfg_options));
+            crate::write_indented(w, &self.cfg_options, indent_level)?;
+            w.write_char('\n')?;
         }
 
-        contents.push(match self.visibility {
-            Visibility::Private => format!("{}mod {} {{\n", indent_string, self.name),
-            Visibility::Public => format!("{}pub mod {} {{\n", indent_string, self.name),
-            Visibility::CrateVisible => {
-                format!("{}pub(crate) mod {} {{\n", indent_string, self.name)
-            }
-        });
+        crate::write_indent(w, indent_level)?;
 
-        let imports: String = self
-            .imports
-            .iter()
-            .map(|s| [import_indent_string.clone(), s.clone(), String::from(";\n")].join(""))
-            .collect();
+        match self.visibility {
+            Visibility::Private => writeln!(w, "mod {} {{", self.name)?,
+            _ => writeln!(w, "{} mod {} {{", self.visibility, self.name)?,
+        }
 
-        if !imports.is_empty() {
-            contents.push(imports);
-            contents.push(String::from("\n"));
+        for import in &self.imports {
+            crate::write_indent(w, indent_level + 1)?;
+            w.write_str(import)?;
+            w.write_str(";\n")?;
         }
 
-        contents.extend(
-            self.components
-                .iter()
-                .map(|s| s.to_rust_string(indent_level + 1)),
-        );
+        if !self.imports.is_empty() && !self.components.is_empty() {
+            w.write_char('\n')?;
+        }
 
-        contents.push(format!("{}}}\n", indent_string));
+        crate::write_components(w, &self.components, &ctx.nested())?;
 
-        return contents.join("");
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
+
+        return Ok(());
     }
 }
 
@@ -191,7 +279,9 @@ impl RustComponentTrait for RustModule {
 mod tests {
     use super::*;
     use crate::rust_component::Field;
-    use crate::RustStruct;
+    use crate::{
+        EnumVariant, RustEnum, RustImplementation, RustMethod, RustStruct, RustText, RustVariable,
+    };
 
     #[test]
     fn test_struct_module() {
@@ -232,4 +322,82 @@ mod tests {
             "mod test_module {\n    use crate::other_module::Struct;\n\n    struct Time<'a, 'b, T> {\n        seconds: u64,\n        minutes: u64,\n        hours: u64,\n    }\n}\n"
         );
     }
+
+    #[test]
+    fn test_nested_module() {
+        let m = RustModule::new("outer")
+            .with_cfg("#[cfg(test)]")
+            .with_component(
+                RustModule::new("inner")
+                    .with_cfg("#[cfg(test)]")
+                    .with_component(
+                        RustEnum::new("Size")
+                            .with_variant(EnumVariant::new_empty("Small"))
+                            .into(),
+                    )
+                    .with_component(
+                        RustImplementation::new("Size")
+                            .with_component(RustVariable::new_const("MAX").with_value("1").into())
+                            .with_component(RustMethod::new("grow").into())
+                            .into(),
+                    )
+                    .into(),
+            );
+
+        assert_eq!(
+            m.to_rust_string(0),
+            "#[cfg(test)]\nmod outer {\n    #[cfg(test)]\n    mod inner {\n        enum Size {\n            Small,\n        }\n\n        impl Size {\n            const MAX = 1;\n\n            fn grow() {\n            }\n        }\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_single_line_components() {
+        let m = RustModule::new("constants")
+            .with_component(RustText::new("type Unit = u8;").into())
+            .with_component(RustVariable::new_const("MAX").with_value("1").into())
+            .with_component(RustMethod::new("max").into());
+
+        assert_eq!(
+            m.to_rust_string(1),
+            "    mod constants {\n        type Unit = u8;\n        const MAX = 1;\n\n        fn max() {\n        }\n    }\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_multi_line_strings() {
+        let m = RustModule::new("queries").with_component(
+            RustText::new("const QUERY: &str = r#\"\nSELECT \"name\"\n  FROM users\n\"#;\n// The end\nconst END: &str = \"a\nb\";").into(),
+        );
+
+        assert_eq!(
+            m.to_rust_string(0),
+            "mod queries {\n    const QUERY: &str = r#\"\nSELECT \"name\"\n  FROM users\n\"#;\n    // The end\n    const END: &str = \"a\nb\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_edit_components() {
+        let mut m = RustModule::new("farm")
+            .with_component(RustStruct::new("Cow").into())
+            .with_component(RustImplementation::new("Cow").into())
+            .with_component(RustMethod::new("feed").into());
+
+        if let Some(RustComponent::Struct(cow)) = m.find_component_mut("Cow") {
+            cow.push_field(Field::private("age", "u64"));
+            cow.find_field_mut("age").unwrap().set_field_type("u8");
+        }
+
+        assert!(matches!(
+            m.remove_component("feed"),
+            Some(RustComponent::Method(_))
+        ));
+        assert!(m.remove_component("feed").is_none());
+
+        m.components_mut().reverse();
+
+        assert_eq!(
+            m.to_rust_string(0),
+            "mod farm {\n    impl Cow {\n    }\n\n    struct Cow {\n        age: u8,\n    }\n}\n"
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use crate::rust_component::{
-    Field, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
+    Field, RenderContext, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
 };
+use crate::source_map::{ComponentGuard, Origin};
+use crate::wrap::Header;
 
 use std::fmt;
 
@@ -33,14 +35,25 @@ use std::fmt;
 /// );
 /// ```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustStruct {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     fields: Vec<Field>,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
     templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     extra: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     cfg: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    docs: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustStruct {
@@ -54,6 +67,8 @@ impl RustStruct {
             lifetimes: Vec::new(),
             extra: String::new(),
             cfg: Vec::new(),
+            docs: String::new(),
+            origin: Origin::default(),
         };
     }
 
@@ -143,6 +158,22 @@ impl RustStruct {
         return self;
     }
 
+    /// Documents the struct with the specified text. Text should not contain any docstring lines.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_struct = RustStruct::new("Cow").with_docs("A cow.").with_cfg("#[derive(Clone)]");
+    ///
+    /// assert_eq!(rust_struct.to_rust_string(0), "/// A cow.\n#[derive(Clone)]\nstruct Cow {\n}\n");
+    /// ```
+    pub fn with_docs(mut self, docs: &str) -> Self {
+        self.set_docs(docs);
+
+        return self;
+    }
+
     /// Appends a field.
     ///
     /// ```
@@ -221,6 +252,119 @@ impl RustStruct {
     pub fn set_cfg(&mut self, cfg: &str) {
         self.cfg = vec![cfg.to_string()];
     }
+
+    /// Documents the struct with the specified text. Text should not contain any docstring lines.
+    pub fn set_docs(&mut self, docs: &str) {
+        self.docs = docs.to_string();
+    }
+
+    /// Renames the struct.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Removes the field called `name`, returning it if it existed.
+    ///
+    /// ```
+    /// use rmod_gen::RustStruct;
+    /// use rmod_gen::rust_component::Field;
+    ///
+    /// let mut rust_struct = RustStruct::new("Cow")
+    ///     .with_field(Field::private("name", "String"))
+    ///     .with_field(Field::private("age", "u64"));
+    ///
+    /// assert_eq!(rust_struct.remove_field("name").unwrap().field_type(), "String");
+    /// assert_eq!(rust_struct.fields().len(), 1);
+    /// ```
+    pub fn remove_field(&mut self, name: &str) -> Option<Field> {
+        let index = self.fields.iter().position(|f| f.name() == name)?;
+
+        return Some(self.fields.remove(index));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn fields(&self) -> &Vec<Field> {
+        return &self.fields;
+    }
+
+    /// The fields of the struct, which can be edited, reordered or removed in place.
+    pub fn fields_mut(&mut self) -> &mut Vec<Field> {
+        return &mut self.fields;
+    }
+
+    /// The field called `name`.
+    pub fn find_field(&self, name: &str) -> Option<&Field> {
+        return self.fields.iter().find(|f| f.name() == name);
+    }
+
+    pub fn find_field_mut(&mut self, name: &str) -> Option<&mut Field> {
+        return self.fields.iter_mut().find(|f| f.name() == name);
+    }
+
+    pub fn templates(&self) -> &Vec<String> {
+        return &self.templates;
+    }
+
+    pub fn lifetimes(&self) -> &Vec<String> {
+        return &self.lifetimes;
+    }
+
+    pub fn extra(&self) -> &str {
+        return &self.extra;
+    }
+
+    /// The attributes placed before the struct, e.g. `#[derive(Clone)]`.
+    pub fn cfg(&self) -> &Vec<String> {
+        return &self.cfg;
+    }
+
+    pub fn docs(&self) -> &str {
+        return &self.docs;
+    }
+
+    /// The type of this struct as it is referred to elsewhere, e.g. `Time<'a, T>`.
+    pub(crate) fn type_string(&self) -> String {
+        let template_names: Vec<String> = self
+            .templates
+            .iter()
+            .map(|t| crate::rust_component::template_name(t).to_string())
+            .collect();
+
+        return format!(
+            "{}{}",
+            self.name,
+            Self::create_template_string(&template_names, &self.lifetimes)
+        );
+    }
+
+    /// Records where the struct was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the struct was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the struct was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The struct as part of a path in a source map, e.g. `struct Cow`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("struct {}", self.name);
+    }
 }
 
 impl Into<RustComponent> for RustStruct {
@@ -232,43 +376,47 @@ impl Into<RustComponent> for RustStruct {
 impl RustTemplateUsage for RustStruct {}
 
 impl RustComponentTrait for RustStruct {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let mut lines = self.cfg.clone();
-
-        let crate_line = match self.visibility {
-            Visibility::Private => format!(
-                "struct {}{}",
-                self.name,
-                Self::create_template_string(&self.templates, &self.lifetimes)
-            ),
-            _ => format!(
-                "{} struct {}{}",
-                self.visibility,
-                self.name,
-                Self::create_template_string(&self.templates, &self.lifetimes)
-            ),
-        };
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        let indent_level = ctx.indent_level();
 
-        if self.extra.is_empty() {
-            lines.push(format!("{} {{", crate_line));
-        } else {
-            lines.push(format!("{} {} {{", crate_line, &self.extra));
+        crate::write_docs(w, &self.docs, "///", indent_level)?;
+
+        for cfg in &self.cfg {
+            crate::write_indent(w, indent_level)?;
+            w.write_str(cfg)?;
+            w.write_char('\n')?;
         }
 
-        let indent_str = crate::indent_string(1);
+        let prefix = match self.visibility {
+            Visibility::Private => format!("struct {}", self.name),
+            _ => format!("{} struct {}", self.visibility, self.name),
+        };
 
-        for field in &self.fields {
-            lines.push([indent_str.clone(), field.to_string(), ",".to_string()].join(""));
+        let header = Header::new(&prefix, " {")
+            .with_generics(Self::create_template_items(
+                &self.templates,
+                &self.lifetimes,
+            ))
+            .with_extra(&self.extra);
+
+        for line in header.lines(indent_level, ctx.options()) {
+            crate::write_indent(w, indent_level)?;
+            w.write_str(&line)?;
+            w.write_char('\n')?;
         }
 
-        lines.push(String::from("}"));
+        for field in &self.fields {
+            crate::write_docs(w, field.docs(), "///", indent_level + 1)?;
+            crate::write_indent(w, indent_level + 1)?;
+            writeln!(w, "{},", field)?;
+        }
 
-        let indent_str = crate::indent_string(indent_level);
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
 
-        return lines
-            .into_iter()
-            .map(|l| [indent_str.clone(), l, String::from("\n")].join(""))
-            .collect();
+        return Ok(());
     }
 }
 
@@ -281,6 +429,7 @@ impl fmt::Display for RustStruct {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rust_component::RenderOptions;
 
     #[test]
     fn simple_test() {
@@ -357,4 +506,17 @@ mod tests {
             "    #[derive(Clone)]\n    struct Time<'a, 'b, T> {\n        seconds: u64,\n        minutes: u64,\n        hours: u64,\n    }\n"
         );
     }
+
+    #[test]
+    fn wrapped_where_clause_test() {
+        let s = RustStruct::new("Time")
+            .with_template("T")
+            .with_extra("where T: Clone + Default, T: Debug")
+            .with_field(Field::private("seconds", "T"));
+
+        assert_eq!(
+            s.to_rust_string_with(0, &RenderOptions::new().with_max_width(40)),
+            "struct Time<T>\nwhere\n    T: Clone + Default,\n    T: Debug,\n{\n    seconds: T,\n}\n"
+        );
+    }
 }
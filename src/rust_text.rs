@@ -1,4 +1,7 @@
-use crate::rust_component::{RustComponent, RustComponentTrait};
+use crate::rust_component::{RenderContext, RustComponent, RustComponentTrait};
+use crate::source_map::{ComponentGuard, Origin};
+
+use std::fmt;
 
 /// Represents some text that can't be represented by any of the other components.
 ///
@@ -11,8 +14,11 @@ use crate::rust_component::{RustComponent, RustComponentTrait};
 /// assert_eq!(component, "#[cfg(test)]".to_string());
 /// ```
 #[derive(Clone, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustText {
     text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustText {
@@ -20,8 +26,52 @@ impl RustText {
     pub fn new(text: &str) -> Self {
         return Self {
             text: text.to_string(),
+            origin: Origin::default(),
         };
     }
+
+    /// Replaces the text.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    pub fn text(&self) -> &str {
+        return &self.text;
+    }
+
+    /// The name of the associated type if this text is one, e.g. `Output` for `type Output = u8;`.
+    pub(crate) fn associated_type_name(&self) -> Option<&str> {
+        let name = self.text.trim_start().strip_prefix("type ")?;
+
+        return name
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .filter(|name| !name.is_empty());
+    }
+
+    /// Records where the text was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the text was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the text was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The first line of the text as part of a path in a source map, e.g. `type Unit = u8;`.
+    pub(crate) fn path_segment(&self) -> String {
+        let line = self.text.lines().map(str::trim).find(|l| !l.is_empty());
+
+        return line.unwrap_or_default().to_string();
+    }
 }
 
 impl Into<RustComponent> for RustText {
@@ -31,7 +81,9 @@ impl Into<RustComponent> for RustText {
 }
 
 impl RustComponentTrait for RustText {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        return format!("{}{}", crate::indent_string(indent_level), &self.text);
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        return crate::write_indented(w, &self.text, ctx.indent_level());
     }
 }
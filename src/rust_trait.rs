@@ -1,4 +1,11 @@
-use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage, Visibility};
+use crate::rust_component::{
+    RenderContext, RustComponent, RustComponentTrait, RustTemplateUsage, Visibility,
+};
+use crate::source_map::{ComponentGuard, Origin};
+use crate::wrap::Header;
+use crate::{RustImplementation, RustMethod, RustText, RustVariable};
+
+use std::fmt;
 
 /// Represents a trait in Rust.
 ///
@@ -25,18 +32,28 @@ use crate::rust_component::{RustComponent, RustComponentTrait, RustTemplateUsage
 ///                         .into()
 ///                 );
 ///
-/// assert_eq!(r_trait.to_rust_string(0), "pub trait Explosive<'a, T>: std::fmt::Debug {\n    fn my_method();\n\n}\n")
+/// assert_eq!(r_trait.to_rust_string(0), "pub trait Explosive<'a, T>: std::fmt::Debug {\n    fn my_method();\n}\n")
 /// ```
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustTrait {
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
+    #[cfg_attr(feature = "serde", serde(default))]
     bounds: Vec<String>,
-    components: Vec<RustComponent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    items: Vec<TraitItem>,
+    #[cfg_attr(feature = "serde", serde(default))]
     lifetimes: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     templates: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
     cfg: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     extra: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 impl RustTrait {
@@ -46,11 +63,12 @@ impl RustTrait {
             name: name.to_string(),
             visibility: Visibility::Private,
             bounds: Vec::new(),
-            components: Vec::new(),
+            items: Vec::new(),
             lifetimes: Vec::new(),
             templates: Vec::new(),
             cfg: String::new(),
             extra: String::new(),
+            origin: Origin::default(),
         };
     }
 
@@ -77,7 +95,8 @@ impl RustTrait {
         return self;
     }
 
-    /// Appends a component to the trait.
+    /// Appends a component to the trait. Methods without a body become required methods and
+    /// methods with a body become provided methods, see [`TraitItem`].
     ///
     /// <b>NOTE:</b> Whilst this method accepts any RustComponent the ownership is on the user of this
     /// method to ensure that only components that are valid in this situation are added,
@@ -88,6 +107,28 @@ impl RustTrait {
         return self;
     }
 
+    /// Appends an item to the trait.
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, RustTrait, TraitItem};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_trait = RustTrait::new("Shape")
+    ///     .with_item(TraitItem::new_type("Unit", vec![], None))
+    ///     .with_item(TraitItem::new_required(RustMethod::new("area").with_argument("&self").with_return_type("f64")))
+    ///     .with_item(TraitItem::new_provided(RustMethod::new("is_empty").with_argument("&self").with_return_type("bool").with_body("self.area() == 0.0")));
+    ///
+    /// assert_eq!(
+    ///     rust_trait.to_rust_string(0),
+    ///     "trait Shape {\n    type Unit;\n\n    fn area(&self) -> f64;\n\n    fn is_empty(&self) -> bool {\n        self.area() == 0.0\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn with_item(mut self, item: TraitItem) -> Self {
+        self.push_item(item);
+
+        return self;
+    }
+
     /// Appends a lifetime. The lifetime should be only the identifier. i.e. to create a lifetime " 'a "
     ///
     /// ```
@@ -164,13 +205,19 @@ impl RustTrait {
         self.bounds.push(bound.to_string());
     }
 
-    /// Appends a component to the trait.
+    /// Appends a component to the trait. Methods without a body become required methods and
+    /// methods with a body become provided methods, see [`TraitItem`].
     ///
     /// <b>NOTE:</b> Whilst this method accepts any RustComponent the ownership is on the user of this
     /// method to ensure that only components that are valid in this situation are added,
     /// e.g. methods or variables but not structs.
     pub fn push_component(&mut self, component: RustComponent) {
-        self.components.push(component);
+        self.items.push(component.into());
+    }
+
+    /// Appends an item to the trait.
+    pub fn push_item(&mut self, item: TraitItem) {
+        self.items.push(item);
     }
 
     /// Appends a lifetime. The lifetime should be only the identifier. i.e. to create a lifetime " 'a "
@@ -223,6 +270,324 @@ impl RustTrait {
     pub fn set_extra(&mut self, extra: &str) {
         self.extra = extra.to_string();
     }
+
+    /// Generates an impl block of this trait for `target`, see [`RustImplementation::for_trait`].
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, RustTrait};
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let rust_trait = RustTrait::new("Shape")
+    ///     .with_component(RustMethod::new("area").with_argument("&self").with_return_type("f64").without_body().into());
+    ///
+    /// assert_eq!(
+    ///     rust_trait.implementation_skeleton("Square").to_rust_string(0),
+    ///     "impl Shape for Square {\n    fn area(&self) -> f64 {\n        todo!()\n    }\n}\n"
+    /// );
+    /// ```
+    pub fn implementation_skeleton(&self, target: &str) -> RustImplementation {
+        return RustImplementation::for_trait(self, target);
+    }
+
+    /// Renames the trait.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Removes the item called `name`, returning it if it existed.
+    pub fn remove_item(&mut self, name: &str) -> Option<TraitItem> {
+        let index = self.items.iter().position(|i| i.name() == Some(name))?;
+
+        return Some(self.items.remove(index));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    /// The supertraits of the trait, e.g. `Debug`.
+    pub fn bounds(&self) -> &Vec<String> {
+        return &self.bounds;
+    }
+
+    pub fn items(&self) -> &Vec<TraitItem> {
+        return &self.items;
+    }
+
+    /// The items of the trait, which can be edited, reordered or removed in place.
+    pub fn items_mut(&mut self) -> &mut Vec<TraitItem> {
+        return &mut self.items;
+    }
+
+    /// The method, type or constant called `name`.
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, RustTrait, TraitItem};
+    ///
+    /// let rust_trait = RustTrait::new("Shape")
+    ///     .with_item(TraitItem::new_required(RustMethod::new("area")));
+    ///
+    /// assert!(rust_trait.find_item("area").unwrap().is_required());
+    /// ```
+    pub fn find_item(&self, name: &str) -> Option<&TraitItem> {
+        return self.items.iter().find(|i| i.name() == Some(name));
+    }
+
+    pub fn find_item_mut(&mut self, name: &str) -> Option<&mut TraitItem> {
+        return self.items.iter_mut().find(|i| i.name() == Some(name));
+    }
+
+    pub fn lifetimes(&self) -> &Vec<String> {
+        return &self.lifetimes;
+    }
+
+    pub fn templates(&self) -> &Vec<String> {
+        return &self.templates;
+    }
+
+    pub fn cfg(&self) -> &str {
+        return &self.cfg;
+    }
+
+    pub fn extra(&self) -> &str {
+        return &self.extra;
+    }
+
+    /// Records where the trait was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the trait was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the trait was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The trait as part of a path in a source map, e.g. `trait Shape`.
+    pub(crate) fn path_segment(&self) -> String {
+        return format!("trait {}", self.name);
+    }
+}
+
+/// Represents an item inside of a trait definition.
+#[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraitItem {
+    /// A method without a default body, which every implementation must provide.
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, TraitItem};
+    ///
+    /// // fn area(&self) -> f64;
+    /// let item = TraitItem::new_required(RustMethod::new("area").with_argument("&self").with_return_type("f64"));
+    /// ```
+    RequiredMethod(RustMethod),
+    /// A method with a default body.
+    ///
+    /// ```
+    /// use rmod_gen::{RustMethod, TraitItem};
+    ///
+    /// // fn is_empty(&self) -> bool {
+    /// //     self.area() == 0.0
+    /// // }
+    /// let item = TraitItem::new_provided(
+    ///     RustMethod::new("is_empty")
+    ///         .with_argument("&self")
+    ///         .with_return_type("bool")
+    ///         .with_body("self.area() == 0.0"),
+    /// );
+    /// ```
+    ProvidedMethod(RustMethod),
+    /// An associated type, optionally with bounds and a default.
+    ///
+    /// ```
+    /// use rmod_gen::TraitItem;
+    ///
+    /// // type Output: Clone;
+    /// let item = TraitItem::new_type("Output", vec!["Clone".to_string()], None);
+    /// ```
+    AssociatedType {
+        name: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        bounds: Vec<String>,
+        default: Option<String>,
+    },
+    /// An associated constant, optionally with a default value.
+    ///
+    /// ```
+    /// use rmod_gen::TraitItem;
+    ///
+    /// // const SIDES: u32 = 4;
+    /// let item = TraitItem::new_const("SIDES", "u32", Some("4"));
+    /// ```
+    AssociatedConst {
+        name: String,
+        #[cfg_attr(feature = "serde", serde(rename = "type"))]
+        tp: String,
+        default: Option<String>,
+    },
+    /// Any other component, such as a macro invocation, which is generated as is.
+    Other(RustComponent),
+}
+
+impl TraitItem {
+    /// Creates a new required method, any body on the method is removed.
+    pub fn new_required(mut method: RustMethod) -> Self {
+        method.set_with_body(false);
+
+        return Self::RequiredMethod(method);
+    }
+
+    /// Creates a new provided method, the body of the method is the default implementation.
+    pub fn new_provided(mut method: RustMethod) -> Self {
+        method.set_with_body(true);
+
+        return Self::ProvidedMethod(method);
+    }
+
+    /// Creates a new associated type.
+    pub fn new_type(name: &str, bounds: Vec<String>, default: Option<&str>) -> Self {
+        return Self::AssociatedType {
+            name: name.to_string(),
+            bounds,
+            default: default.map(str::to_string),
+        };
+    }
+
+    /// Creates a new associated constant.
+    pub fn new_const(name: &str, tp: &str, default: Option<&str>) -> Self {
+        return Self::AssociatedConst {
+            name: name.to_string(),
+            tp: tp.to_string(),
+            default: default.map(str::to_string),
+        };
+    }
+
+    /// Returns true if an implementation of the trait must provide this item.
+    pub fn is_required(&self) -> bool {
+        return match self {
+            TraitItem::RequiredMethod(_) => true,
+            TraitItem::ProvidedMethod(_) | TraitItem::Other(_) => false,
+            TraitItem::AssociatedType { default, .. }
+            | TraitItem::AssociatedConst { default, .. } => default.is_none(),
+        };
+    }
+
+    /// The name of the method, type or constant. Other components have no name.
+    pub fn name(&self) -> Option<&str> {
+        return match self {
+            TraitItem::RequiredMethod(method) | TraitItem::ProvidedMethod(method) => {
+                Some(method.name())
+            }
+            TraitItem::AssociatedType { name, .. } | TraitItem::AssociatedConst { name, .. } => {
+                Some(name)
+            }
+            TraitItem::Other(_) => None,
+        };
+    }
+
+    /// The item as it would appear in an implementation that has not been filled in yet.
+    pub(crate) fn skeleton(&self) -> Option<RustComponent> {
+        if !self.is_required() {
+            return None;
+        }
+
+        return match self {
+            TraitItem::RequiredMethod(method) => {
+                let mut method = method.clone();
+                method.set_with_body(true);
+                method.set_body("todo!()");
+
+                Some(method.into())
+            }
+            TraitItem::AssociatedType { name, bounds, .. } if bounds.is_empty() => {
+                Some(RustText::new(&format!("type {} = ();", name)).into())
+            }
+            TraitItem::AssociatedType { name, bounds, .. } => Some(
+                RustText::new(&format!(
+                    "type {} = (); // TODO: a type implementing {}",
+                    name,
+                    bounds.join(" + ")
+                ))
+                .into(),
+            ),
+            TraitItem::AssociatedConst { name, tp, .. } => Some(
+                RustVariable::new_const(name)
+                    .with_type(tp)
+                    .with_value("todo!()")
+                    .into(),
+            ),
+            TraitItem::ProvidedMethod(_) | TraitItem::Other(_) => None,
+        };
+    }
+
+    /// Associated types and constants take a single line and are grouped together.
+    fn is_single_line(&self) -> bool {
+        return matches!(
+            self,
+            TraitItem::AssociatedType { .. } | TraitItem::AssociatedConst { .. }
+        );
+    }
+
+    pub(crate) fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        match self {
+            TraitItem::RequiredMethod(method) | TraitItem::ProvidedMethod(method) => {
+                method.write_rust(&mut crate::TrimTrailingNewlines::new(w), ctx)?;
+            }
+            TraitItem::AssociatedType {
+                name,
+                bounds,
+                default,
+            } => {
+                crate::write_indent(w, ctx.indent_level())?;
+                write!(w, "type {}", name)?;
+
+                if !bounds.is_empty() {
+                    write!(w, ": {}", bounds.join(" + "))?;
+                }
+
+                if let Some(default) = default {
+                    write!(w, " = {}", default)?;
+                }
+
+                w.write_char(';')?;
+            }
+            TraitItem::AssociatedConst { name, tp, default } => {
+                crate::write_indent(w, ctx.indent_level())?;
+
+                match default {
+                    Some(default) => write!(w, "const {}: {} = {};", name, tp, default)?,
+                    None => write!(w, "const {}: {};", name, tp)?,
+                }
+            }
+            TraitItem::Other(component) => {
+                component.write_rust(&mut crate::TrimTrailingNewlines::new(w), ctx)?;
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+impl From<RustComponent> for TraitItem {
+    fn from(component: RustComponent) -> Self {
+        return match component {
+            RustComponent::Method(method) if method.has_body() => Self::ProvidedMethod(method),
+            RustComponent::Method(method) => Self::RequiredMethod(method),
+            other => Self::Other(other),
+        };
+    }
 }
 
 impl Into<RustComponent> for RustTrait {
@@ -234,39 +599,154 @@ impl Into<RustComponent> for RustTrait {
 impl RustTemplateUsage for RustTrait {}
 
 impl RustComponentTrait for RustTrait {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let base_indent_string = crate::indent_string(indent_level);
-        let mut components = vec![base_indent_string.clone()];
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        let indent_level = ctx.indent_level();
 
         if !self.cfg.is_empty() {
-            components.push(format!("{}\n", self.cfg));
-            components.push(base_indent_string.clone());
+            crate::write_indented(w, &self.cfg, indent_level)?;
+            w.write_char('\n')?;
         }
 
-        if self.visibility != Visibility::Private {
-            components.push(format!("{} ", self.visibility));
-        }
+        crate::write_indent(w, indent_level)?;
+
+        let prefix = match self.visibility {
+            Visibility::Private => format!("trait {}", self.name),
+            _ => format!("{} trait {}", self.visibility, self.name),
+        };
 
-        components.push(format!("trait {}", self.name));
-        components.push(Self::create_template_string(&self.templates, &self.lifetimes));
+        let mut header = Header::new(&prefix, " {")
+            .with_generics(Self::create_template_items(
+                &self.templates,
+                &self.lifetimes,
+            ))
+            .with_extra(&self.extra);
 
         if !self.bounds.is_empty() {
-            components.push(format!(": {}", self.bounds.join(" + ")));
+            header = header.with_suffix(&format!(": {}", self.bounds.join(" + ")));
         }
 
-        if !self.extra.is_empty() {
-            components.push(format!(" {}", self.extra));
+        for (i, line) in header.lines(indent_level, ctx.options()).iter().enumerate() {
+            if i > 0 {
+                w.write_char('\n')?;
+                crate::write_indent(w, indent_level)?;
+            }
+
+            w.write_str(line)?;
         }
 
-        components.push(" {\n".to_string());
+        w.write_char('\n')?;
+
+        for (i, item) in self.items.iter().enumerate() {
+            // Items are separated by an empty line, unless they are both single line items.
+            if i > 0 && !(item.is_single_line() && self.items[i - 1].is_single_line()) {
+                w.write_char('\n')?;
+            }
 
-        for comp in &self.components {
-            components.push(comp.to_rust_string(indent_level + 1));
-            components.push("\n".to_string());
+            item.write_rust(w, &ctx.nested())?;
+            w.write_char('\n')?;
         }
 
-        components.push(format!("{}}}\n", base_indent_string));
+        crate::write_indent(w, indent_level)?;
+        w.write_str("}\n")?;
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_and_provided_methods() {
+        let t = RustTrait::new("Shape")
+            .with_component(
+                RustMethod::new("area")
+                    .with_argument("&self")
+                    .with_return_type("f64")
+                    .without_body()
+                    .into(),
+            )
+            .with_component(
+                RustMethod::new("double_area")
+                    .with_argument("&self")
+                    .with_return_type("f64")
+                    .with_body("self.area() * 2.0")
+                    .into(),
+            );
+
+        assert_eq!(
+            t.to_rust_string(0),
+            "trait Shape {\n    fn area(&self) -> f64;\n\n    fn double_area(&self) -> f64 {\n        self.area() * 2.0\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_associated_items_grouped() {
+        let t = RustTrait::new("Shape")
+            .with_item(TraitItem::new_type("Unit", vec!["Copy".to_string()], None))
+            .with_item(TraitItem::new_const("SIDES", "u32", Some("4")))
+            .with_item(TraitItem::new_required(RustMethod::new("sides")));
+
+        assert_eq!(
+            t.to_rust_string(1),
+            "    trait Shape {\n        type Unit: Copy;\n        const SIDES: u32 = 4;\n\n        fn sides();\n    }\n"
+        );
+    }
+
+    #[test]
+    fn test_is_required() {
+        assert!(TraitItem::new_required(RustMethod::new("a")).is_required());
+        assert!(!TraitItem::new_provided(RustMethod::new("a")).is_required());
+        assert!(TraitItem::new_type("A", vec![], None).is_required());
+        assert!(!TraitItem::new_type("A", vec![], Some("u8")).is_required());
+        assert!(TraitItem::new_const("A", "u8", None).is_required());
+        assert!(!TraitItem::new_const("A", "u8", Some("0")).is_required());
+    }
+
+    #[test]
+    fn test_implementation_skeleton() {
+        let t = RustTrait::new("Converter")
+            .with_lifetime("a")
+            .with_template("T: Clone")
+            .with_item(TraitItem::new_type("Output", vec![], None))
+            .with_item(TraitItem::new_type("Error", vec![], Some("()")))
+            .with_item(TraitItem::new_const("NAME", "&'static str", None))
+            .with_item(TraitItem::new_required(
+                RustMethod::new("convert")
+                    .with_argument("&self")
+                    .with_argument("input: &'a T")
+                    .with_return_type("Self::Output"),
+            ))
+            .with_item(TraitItem::new_provided(
+                RustMethod::new("name")
+                    .with_argument("&self")
+                    .with_body("Self::NAME"),
+            ));
+
+        assert_eq!(
+            t.implementation_skeleton("Celsius").to_rust_string(0),
+            "impl<'a, T: Clone> Converter<'a, T> for Celsius {\n    type Output = ();\n    const NAME: &'static str = todo!();\n\n    fn convert(&self, input: &'a T) -> Self::Output {\n        todo!()\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_edit_items() {
+        let mut t = RustTrait::new("Shape")
+            .with_item(TraitItem::new_required(RustMethod::new("area")))
+            .with_item(TraitItem::new_type("Unit", Vec::new(), None));
+
+        if let Some(TraitItem::RequiredMethod(area)) = t.find_item_mut("area") {
+            area.push_argument("&self");
+        }
 
-        return components.join("");
+        assert!(t.remove_item("Unit").is_some());
+        assert!(t.find_item("Unit").is_none());
+        assert_eq!(
+            t.to_rust_string(0),
+            "trait Shape {\n    fn area(&self);\n}\n"
+        );
     }
 }
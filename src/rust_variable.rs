@@ -1,4 +1,7 @@
-use crate::rust_component::{RustComponent, RustComponentTrait, Visibility};
+use crate::rust_component::{RenderContext, RustComponent, RustComponentTrait, Visibility};
+use crate::source_map::{ComponentGuard, Origin};
+
+use std::fmt;
 
 /// Represents a variable in Rust.
 ///
@@ -15,16 +18,25 @@ use crate::rust_component::{RustComponent, RustComponentTrait, Visibility};
 /// assert_eq!(variable, "let var: &str = \"carton\";");
 /// ```
 #[derive(Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RustVariable {
+    #[cfg_attr(feature = "serde", serde(default))]
     visibility: Visibility,
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     value: String,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     tp: String,
+    #[cfg_attr(feature = "serde", serde(rename = "kind"))]
     variable_type: VariableType,
+    #[cfg_attr(feature = "serde", serde(default))]
     is_mut: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    origin: Origin,
 }
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum VariableType {
     Static,
     Const,
@@ -41,6 +53,7 @@ impl RustVariable {
             tp: String::new(),
             variable_type: VariableType::Regular,
             is_mut: false,
+            origin: Origin::default(),
         };
     }
 
@@ -53,6 +66,7 @@ impl RustVariable {
             tp: String::new(),
             variable_type: VariableType::Const,
             is_mut: false,
+            origin: Origin::default(),
         };
     }
 
@@ -65,6 +79,7 @@ impl RustVariable {
             tp: String::new(),
             variable_type: VariableType::Static,
             is_mut: false,
+            origin: Origin::default(),
         };
     }
 
@@ -191,6 +206,68 @@ impl RustVariable {
     pub fn set_visibility(&mut self, visibility: Visibility) {
         self.visibility = visibility;
     }
+
+    /// Renames the variable.
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        return self.visibility;
+    }
+
+    pub fn value(&self) -> &str {
+        return &self.value;
+    }
+
+    /// The explicit type of the variable, which is empty if it has none.
+    pub fn tp(&self) -> &str {
+        return &self.tp;
+    }
+
+    pub fn is_mut(&self) -> bool {
+        return self.is_mut;
+    }
+
+    pub fn is_const(&self) -> bool {
+        return self.variable_type == VariableType::Const;
+    }
+
+    pub fn is_static(&self) -> bool {
+        return self.variable_type == VariableType::Static;
+    }
+
+    /// Records where the variable was generated from, see [`SourceMap`](crate::SourceMap).
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.set_origin(origin);
+
+        return self;
+    }
+
+    /// Records where the variable was generated from.
+    pub fn set_origin(&mut self, origin: &str) {
+        self.origin = Origin::new(origin);
+    }
+
+    /// Where the variable was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.get();
+    }
+
+    /// The variable as part of a path in a source map, e.g. `const MAX`.
+    pub(crate) fn path_segment(&self) -> String {
+        let keyword = match self.variable_type {
+            VariableType::Static => "static",
+            VariableType::Const => "const",
+            VariableType::Regular => "let",
+        };
+
+        return format!("{} {}", keyword, self.name);
+    }
 }
 
 impl Into<RustComponent> for RustVariable {
@@ -200,39 +277,38 @@ impl Into<RustComponent> for RustVariable {
 }
 
 impl RustComponentTrait for RustVariable {
-    fn to_rust_string(&self, indent_level: usize) -> String {
-        let mut components = vec![crate::indent_string(indent_level)];
+    fn write_rust(&self, w: &mut dyn fmt::Write, ctx: &RenderContext) -> fmt::Result {
+        let _component = ComponentGuard::begin(w, ctx, self.origin(), || self.path_segment())?;
+
+        crate::write_indent(w, ctx.indent_level())?;
 
         if self.visibility != Visibility::Private {
-            components.push(self.visibility.to_string());
-            components.push(String::from(' '));
+            write!(w, "{} ", self.visibility)?;
         }
 
-        components.push(match self.variable_type {
-            VariableType::Static => String::from("static "),
-            VariableType::Const => String::from("const "),
-            VariableType::Regular => String::from("let "),
-        });
+        w.write_str(match self.variable_type {
+            VariableType::Static => "static ",
+            VariableType::Const => "const ",
+            VariableType::Regular => "let ",
+        })?;
 
         if self.is_mut {
-            components.push(String::from("mut "));
+            w.write_str("mut ")?;
         }
 
-        components.push(self.name.clone());
+        w.write_str(&self.name)?;
 
         if !self.tp.is_empty() {
-            components.push(String::from(": "));
-            components.push(self.tp.clone());
+            write!(w, ": {}", self.tp)?;
         }
 
         if !self.value.is_empty() {
-            components.push(String::from(" = "));
-            components.push(self.value.clone());
+            write!(w, " = {}", self.value)?;
         }
 
-        components.push(String::from(";"));
+        w.write_char(';')?;
 
-        return components.join("");
+        return Ok(());
     }
 }
 
@@ -0,0 +1,547 @@
+use crate::diff::{Change, ChangeKind, DiffItem};
+use crate::rust_component::{RustComponent, Visibility};
+use crate::{RustFile, RustImplementation, TraitItem};
+
+use std::fmt;
+
+/// Whether a change can break code using the public API of the generated crate.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Compatibility {
+    Breaking,
+    Compatible,
+}
+
+/// The part of the version which must be increased for a set of changes, following semver.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VersionBump {
+    /// Only private items or the bodies of methods changed.
+    Patch,
+    /// Public items were added or changed in a compatible way.
+    Minor,
+    /// Some changes are breaking.
+    Major,
+}
+
+/// A change between two files classified by its effect on the public API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SemverChange<'a> {
+    change: Change<'a>,
+    public: bool,
+    compatibility: Compatibility,
+    reason: &'static str,
+}
+
+/// The changes between two files, classified as breaking or compatible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SemverReport<'a> {
+    changes: Vec<SemverChange<'a>>,
+}
+
+impl RustFile {
+    /// Classifies the differences between this file and `new`, as found by [`RustFile::diff`],
+    /// as breaking or compatible changes to the public API. An item is public if it and every
+    /// module containing it are `pub`, and the items of an implementation are only public if the
+    /// type it is for is. The following changes are breaking:
+    ///
+    /// - removing a public item or making it private,
+    /// - adding a field to a public struct without `#[non_exhaustive]` which can be constructed,
+    /// - adding a variant to a public enum without `#[non_exhaustive]`,
+    /// - adding a required item to a public trait,
+    /// - changing the signature of a public method, the type of a public field, constant or
+    ///   static, or the declaration of a public type, trait or implementation.
+    ///
+    /// Changes to private items and to the bodies of methods are compatible and only need a patch
+    /// release, while adding public items or making private items public needs a minor release.
+    /// Text is not considered part of the public API.
+    ///
+    /// ```
+    /// use rmod_gen::{EnumVariant, RustEnum, RustFile, VersionBump};
+    /// use rmod_gen::rust_component::Visibility;
+    ///
+    /// let opcode = RustEnum::new("Opcode")
+    ///     .with_visibility(Visibility::Public)
+    ///     .with_variant(EnumVariant::new_empty("Nop"));
+    ///
+    /// let old = RustFile::new().with_component(opcode.clone().into());
+    /// let new = RustFile::new()
+    ///     .with_component(opcode.with_variant(EnumVariant::new_empty("Halt")).into());
+    ///
+    /// let report = old.semver_report(&new);
+    ///
+    /// assert!(report.is_breaking());
+    /// assert_eq!(report.required_bump(), Some(VersionBump::Major));
+    /// assert_eq!(
+    ///     report.changes()[0].to_string(),
+    ///     "breaking: enum `Opcode` gained variant `Halt` (variant added to an exhaustive enum)"
+    /// );
+    /// ```
+    pub fn semver_report<'a>(&'a self, new: &'a RustFile) -> SemverReport<'a> {
+        let changes = self
+            .diff(new)
+            .into_iter()
+            .map(|change| classify(change, new.components()))
+            .collect();
+
+        return SemverReport { changes };
+    }
+}
+
+impl<'a> SemverReport<'a> {
+    pub fn changes(&self) -> &[SemverChange<'a>] {
+        return &self.changes;
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &SemverChange<'a>> {
+        return self.changes.iter().filter(|c| c.is_breaking());
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        return self.breaking_changes().next().is_some();
+    }
+
+    /// The smallest version bump which covers every change, or `None` if nothing changed.
+    pub fn required_bump(&self) -> Option<VersionBump> {
+        return self
+            .changes
+            .iter()
+            .map(|c| match (c.compatibility, c.public) {
+                (Compatibility::Breaking, _) => VersionBump::Major,
+                (Compatibility::Compatible, true) => VersionBump::Minor,
+                (Compatibility::Compatible, false) => VersionBump::Patch,
+            })
+            .max();
+    }
+}
+
+impl<'a> SemverChange<'a> {
+    pub fn change(&self) -> &Change<'a> {
+        return &self.change;
+    }
+
+    /// Whether the change affects the public API, including compatible additions.
+    pub fn is_public(&self) -> bool {
+        return self.public;
+    }
+
+    pub fn compatibility(&self) -> Compatibility {
+        return self.compatibility;
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        return self.compatibility == Compatibility::Breaking;
+    }
+
+    /// Why the change is breaking or compatible, e.g. `public item removed`.
+    pub fn reason(&self) -> &'static str {
+        return self.reason;
+    }
+}
+
+impl fmt::Display for SemverChange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compatibility = match self.compatibility {
+            Compatibility::Breaking => "breaking",
+            Compatibility::Compatible => "compatible",
+        };
+
+        return write!(f, "{}: {} ({})", compatibility, self.change, self.reason);
+    }
+}
+
+impl fmt::Display for SemverReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", change)?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// Classifies a change between two files, where `root` are the components of the new file.
+fn classify<'a>(change: Change<'a>, root: &'a [RustComponent]) -> SemverChange<'a> {
+    let parent = change.parent();
+    let parents_public = is_reachable(change.parents(), root);
+
+    let (public, compatibility, reason) =
+        match (change.kind(), change.old_item(), change.new_item()) {
+            (ChangeKind::BodyChanged, _, _) => (false, Compatibility::Compatible, "body changed"),
+            (ChangeKind::Removed, Some(old), _)
+                if parents_public && is_visible(old, parent, root) =>
+            {
+                (true, Compatibility::Breaking, "public item removed")
+            }
+            (ChangeKind::Added, _, Some(new)) if parents_public => added(new, parent, root),
+            (ChangeKind::Changed, Some(old), Some(new))
+                if parents_public && is_visible(old, parent, root) =>
+            {
+                changed(old, new, parent, root)
+            }
+            (ChangeKind::Changed, _, Some(new))
+                if parents_public && is_visible(new, parent, root) =>
+            {
+                (true, Compatibility::Compatible, "private item made public")
+            }
+            _ => (false, Compatibility::Compatible, "private item"),
+        };
+
+    return SemverChange {
+        change,
+        public,
+        compatibility,
+        reason,
+    };
+}
+
+fn added(
+    new: DiffItem,
+    parent: Option<DiffItem>,
+    root: &[RustComponent],
+) -> (bool, Compatibility, &'static str) {
+    return match (new, parent) {
+        (DiffItem::Field(field), Some(DiffItem::Component(RustComponent::Struct(s)))) => {
+            // A struct with a private field can't be constructed outside of the crate.
+            let constructible = s
+                .fields()
+                .iter()
+                .all(|f| f.name() == field.name() || f.visibility() == Visibility::Public);
+
+            if constructible && !is_non_exhaustive(s.cfg()) {
+                (
+                    true,
+                    Compatibility::Breaking,
+                    "field added to an exhaustive struct",
+                )
+            } else {
+                (
+                    field.visibility() == Visibility::Public,
+                    Compatibility::Compatible,
+                    "field added",
+                )
+            }
+        }
+        (DiffItem::Variant(_), Some(DiffItem::Component(RustComponent::Enum(e)))) => {
+            if is_non_exhaustive(e.cfg()) {
+                (
+                    true,
+                    Compatibility::Compatible,
+                    "variant added to a non-exhaustive enum",
+                )
+            } else {
+                (
+                    true,
+                    Compatibility::Breaking,
+                    "variant added to an exhaustive enum",
+                )
+            }
+        }
+        (DiffItem::TraitItem(item), _) if item.is_required() => (
+            true,
+            Compatibility::Breaking,
+            "required item added to a trait",
+        ),
+        (new, parent) if is_visible(new, parent, root) => {
+            (true, Compatibility::Compatible, "public item added")
+        }
+        _ => (false, Compatibility::Compatible, "private item"),
+    };
+}
+
+fn changed(
+    old: DiffItem,
+    new: DiffItem,
+    parent: Option<DiffItem>,
+    root: &[RustComponent],
+) -> (bool, Compatibility, &'static str) {
+    if !is_visible(new, parent, root) {
+        return (true, Compatibility::Breaking, "public item made private");
+    }
+
+    return match (old, new) {
+        // Methods are declared by their signature, edits of their body are not changes.
+        (
+            DiffItem::Component(RustComponent::Method(_)),
+            DiffItem::Component(RustComponent::Method(_)),
+        )
+        | (
+            DiffItem::TraitItem(TraitItem::RequiredMethod(_)),
+            DiffItem::TraitItem(TraitItem::RequiredMethod(_)),
+        )
+        | (
+            DiffItem::TraitItem(TraitItem::ProvidedMethod(_)),
+            DiffItem::TraitItem(TraitItem::ProvidedMethod(_)),
+        ) => (true, Compatibility::Breaking, "signature changed"),
+        (DiffItem::TraitItem(a), DiffItem::TraitItem(b)) if b.is_required() && !a.is_required() => {
+            (
+                true,
+                Compatibility::Breaking,
+                "default removed from a trait item",
+            )
+        }
+        (
+            DiffItem::Component(RustComponent::Variable(a)),
+            DiffItem::Component(RustComponent::Variable(b)),
+        ) => {
+            if a.tp() == b.tp() && a.is_mut() == b.is_mut() {
+                (true, Compatibility::Compatible, "value changed")
+            } else {
+                (true, Compatibility::Breaking, "type changed")
+            }
+        }
+        (DiffItem::Field(_), DiffItem::Field(_)) => {
+            (true, Compatibility::Breaking, "field type changed")
+        }
+        (DiffItem::Component(RustComponent::Module(_)), _) => {
+            (true, Compatibility::Compatible, "module attributes changed")
+        }
+        _ => (true, Compatibility::Breaking, "declaration changed"),
+    };
+}
+
+/// Whether every item on the path to an item is visible outside of the crate.
+fn is_reachable(parents: &[DiffItem], root: &[RustComponent]) -> bool {
+    return parents
+        .iter()
+        .enumerate()
+        .all(|(i, item)| is_visible(*item, i.checked_sub(1).map(|p| parents[p]), root));
+}
+
+/// Whether an item is visible outside of the crate when its parent is. `root` are the components
+/// at the root of the file, used to find the types implementations are for.
+fn is_visible(item: DiffItem, parent: Option<DiffItem>, root: &[RustComponent]) -> bool {
+    let public = |visibility: Visibility| visibility == Visibility::Public;
+
+    return match item {
+        DiffItem::Component(component) => match component {
+            RustComponent::Module(o) => public(o.visibility()),
+            RustComponent::Struct(o) => public(o.visibility()),
+            RustComponent::Enum(o) => public(o.visibility()),
+            RustComponent::Trait(o) => public(o.visibility()),
+            RustComponent::Variable(o) => public(o.visibility()),
+            // The items of a trait implementation are as visible as the trait.
+            RustComponent::Method(o) => public(o.visibility()) || is_trait_implementation(parent),
+            RustComponent::Implementation(o) => is_target_visible(o, siblings(parent, root)),
+            RustComponent::EnumVariant(_) => true,
+            RustComponent::Text(_) => false,
+        },
+        DiffItem::Field(field) => public(field.visibility()),
+        DiffItem::Variant(_) | DiffItem::TraitItem(_) => true,
+    };
+}
+
+/// The components next to an item with the given parent, i.e. the components of the module
+/// containing it or the root of the file.
+fn siblings<'a>(parent: Option<DiffItem<'a>>, root: &'a [RustComponent]) -> &'a [RustComponent] {
+    return match parent {
+        Some(DiffItem::Component(RustComponent::Module(module))) => module.components(),
+        _ => root,
+    };
+}
+
+/// Whether the type an implementation is for is public. Types which aren't defined next to the
+/// implementation are assumed to be.
+fn is_target_visible(implementation: &RustImplementation, siblings: &[RustComponent]) -> bool {
+    let target = implementation
+        .name()
+        .rsplit(" for ")
+        .next()
+        .unwrap_or_default();
+    let target = target.split('<').next().unwrap_or_default().trim();
+
+    return siblings
+        .iter()
+        .find_map(|component| match component {
+            RustComponent::Struct(o) if o.name() == target => Some(o.visibility()),
+            RustComponent::Enum(o) if o.name() == target => Some(o.visibility()),
+            _ => None,
+        })
+        .is_none_or(|visibility| visibility == Visibility::Public);
+}
+
+fn is_trait_implementation(item: Option<DiffItem>) -> bool {
+    return match item {
+        Some(DiffItem::Component(RustComponent::Implementation(i))) => i.name().contains(" for "),
+        _ => false,
+    };
+}
+
+fn is_non_exhaustive(cfg: &[String]) -> bool {
+    return cfg.iter().any(|c| c.contains("non_exhaustive"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Field;
+    use crate::{RustImplementation, RustMethod, RustModule, RustStruct, RustVariable};
+
+    #[test]
+    fn test_semver_report() {
+        let old = RustFile::new()
+            .with_component(
+                RustModule::new("api")
+                    .with_visibility(Visibility::Public)
+                    .with_component(
+                        RustStruct::new("Header")
+                            .with_visibility(Visibility::Public)
+                            .with_field(Field::new("len", "u16", Visibility::Public))
+                            .into(),
+                    )
+                    .with_component(
+                        RustImplementation::new("Header")
+                            .with_component(
+                                RustMethod::new("len")
+                                    .with_visibility(Visibility::Public)
+                                    .with_argument("&self")
+                                    .with_return_type("u16")
+                                    .into(),
+                            )
+                            .with_component(RustMethod::new("check").with_body("todo!()").into())
+                            .into(),
+                    )
+                    .into(),
+            )
+            .with_component(RustVariable::new_const("MAX").with_type("u8").into());
+        let new = RustFile::new()
+            .with_component(
+                RustModule::new("api")
+                    .with_visibility(Visibility::Public)
+                    .with_component(
+                        RustStruct::new("Header")
+                            .with_visibility(Visibility::Public)
+                            .with_field(Field::new("len", "u16", Visibility::Public))
+                            .with_field(Field::new("flags", "u8", Visibility::Public))
+                            .into(),
+                    )
+                    .with_component(
+                        RustImplementation::new("Header")
+                            .with_component(
+                                RustMethod::new("len")
+                                    .with_visibility(Visibility::Public)
+                                    .with_argument("&self")
+                                    .with_return_type("u32")
+                                    .into(),
+                            )
+                            .with_component(
+                                RustMethod::new("check")
+                                    .with_body("unimplemented!()")
+                                    .into(),
+                            )
+                            .into(),
+                    )
+                    .into(),
+            )
+            .with_component(RustVariable::new_const("MAX").with_type("u8").into())
+            .with_component(
+                RustVariable::new_const("MIN")
+                    .with_visibility(Visibility::Public)
+                    .with_type("u8")
+                    .into(),
+            );
+
+        let report = old.semver_report(&new);
+        let changes: Vec<String> = report.changes().iter().map(|c| c.to_string()).collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                "breaking: struct `api::Header` gained field `flags` (field added to an exhaustive struct)",
+                "breaking: method `api::Header::len` changed from `pub fn len(&self) -> u16;` to `pub fn len(&self) -> u32;` (signature changed)",
+                "compatible: method `api::Header::check` body changed (body changed)",
+                "compatible: added const `MIN` (public item added)",
+            ]
+        );
+        assert_eq!(report.required_bump(), Some(VersionBump::Major));
+        assert_eq!(old.semver_report(&old).required_bump(), None);
+    }
+
+    #[test]
+    fn test_compatible_changes() {
+        let old = RustFile::new().with_component(
+            RustStruct::new("Header")
+                .with_visibility(Visibility::Public)
+                .with_field(Field::new("len", "u16", Visibility::Public))
+                .into(),
+        );
+        let new = RustFile::new().with_component(
+            RustStruct::new("Header")
+                .with_visibility(Visibility::Public)
+                .with_cfg("#[non_exhaustive]")
+                .with_field(Field::new("len", "u16", Visibility::Public))
+                .with_field(Field::private("flags", "u8"))
+                .into(),
+        );
+
+        // Only the attribute of the struct is breaking.
+        let report = old.semver_report(&new);
+
+        assert_eq!(report.breaking_changes().count(), 1);
+        assert_eq!(report.changes()[1].reason(), "field added");
+        assert!(!report.changes()[1].is_public());
+
+        let new = old.clone().with_component(RustMethod::new("helper").into());
+
+        assert_eq!(
+            old.semver_report(&new).required_bump(),
+            Some(VersionBump::Patch)
+        );
+    }
+
+    #[test]
+    fn test_public_method_body_changed() {
+        let method = RustMethod::new("len")
+            .with_visibility(Visibility::Public)
+            .with_return_type("u16");
+        let old = RustFile::new().with_component(method.clone().with_body("0").into());
+        let new = RustFile::new().with_component(method.with_body("1").into());
+
+        let report = old.semver_report(&new);
+
+        assert_eq!(report.changes()[0].reason(), "body changed");
+        assert_eq!(report.required_bump(), Some(VersionBump::Patch));
+    }
+
+    #[test]
+    fn test_private_item_made_public() {
+        let old = RustFile::new().with_component(RustStruct::new("Header").into());
+        let new = RustFile::new().with_component(
+            RustStruct::new("Header")
+                .with_visibility(Visibility::Public)
+                .into(),
+        );
+
+        let report = old.semver_report(&new);
+
+        assert_eq!(report.changes()[0].reason(), "private item made public");
+        assert_eq!(report.required_bump(), Some(VersionBump::Minor));
+    }
+
+    #[test]
+    fn test_implementation_of_private_type() {
+        let len = RustMethod::new("len").with_visibility(Visibility::Public);
+        let old = RustFile::new()
+            .with_component(RustStruct::new("Header").into())
+            .with_component(
+                RustImplementation::new("Header")
+                    .with_component(len.clone().with_return_type("u16").into())
+                    .into(),
+            );
+        let new = RustFile::new()
+            .with_component(RustStruct::new("Header").into())
+            .with_component(
+                RustImplementation::new("Header")
+                    .with_component(len.with_return_type("u32").into())
+                    .into(),
+            );
+
+        let report = old.semver_report(&new);
+
+        assert!(!report.is_breaking());
+        assert_eq!(report.changes()[0].reason(), "private item");
+        assert_eq!(report.required_bump(), Some(VersionBump::Patch));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::rust_component::RenderContext;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Where a component was generated from, as described in [`SourceMap`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub(crate) struct Origin(Option<String>);
+
+impl Origin {
+    pub(crate) fn new(origin: &str) -> Self {
+        return Self(Some(origin.to_string()));
+    }
+
+    pub(crate) fn get(&self) -> Option<&str> {
+        return self.0.as_deref();
+    }
+}
+
+impl PartialEq for Origin {
+    fn eq(&self, _: &Self) -> bool {
+        return true;
+    }
+}
+
+impl Hash for Origin {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// Maps the lines of generated code back to the components which generated them.
+///
+/// Components can also record where they were generated from with `with_origin`, e.g. a line of
+/// a schema, which is then given by [`SourceMapEntry::origin`]. The origin only annotates the
+/// generated code, so it is ignored when comparing or hashing components: two components which
+/// differ only by their origin are equal.
+///
+/// ```
+/// use rmod_gen::{RustFile, RustImplementation, RustMethod, RustModule};
+///
+/// let (code, source_map) = RustFile::new()
+///     .with_component(
+///         RustModule::new("api")
+///             .with_component(
+///                 RustImplementation::new("Foo")
+///                     .with_component(RustMethod::new("bar").with_origin("schema.json:42").into())
+///                     .into(),
+///             )
+///             .into(),
+///     )
+///     .into_rust_code_with_source_map();
+///
+/// assert_eq!(code.lines().nth(2), Some("        fn bar() {"));
+///
+/// let entry = source_map.find(3).unwrap();
+///
+/// assert_eq!(entry.lines(), 3..5);
+/// assert_eq!(entry.path_string(), "mod api / impl Foo / fn bar");
+/// assert_eq!(entry.origin(), Some("schema.json:42"));
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct SourceMap {
+    entries: Vec<SourceMapEntry>,
+}
+
+/// The lines generated by a component.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    lines: Range<usize>,
+    path: Vec<String>,
+    origin: Option<String>,
+}
+
+impl SourceMap {
+    /// The entries of every component, ordered by their first line. Components come before the
+    /// components nested within them.
+    pub fn entries(&self) -> &[SourceMapEntry] {
+        return &self.entries;
+    }
+
+    /// The innermost component which generated `line`, starting at 1.
+    pub fn find(&self, line: usize) -> Option<&SourceMapEntry> {
+        return self.entries.iter().rev().find(|e| e.lines.contains(&line));
+    }
+}
+
+impl SourceMapEntry {
+    /// The lines generated by the component, starting at 1. The end of the range is exclusive.
+    pub fn lines(&self) -> Range<usize> {
+        return self.lines.clone();
+    }
+
+    /// The path of the component, from the root component of the file, e.g.
+    /// `["mod api", "impl Foo", "fn bar"]`.
+    pub fn path(&self) -> &[String] {
+        return &self.path;
+    }
+
+    /// The path of the component separated by slashes, e.g. `mod api / impl Foo / fn bar`.
+    pub fn path_string(&self) -> String {
+        return self.path.join(" / ");
+    }
+
+    /// Where the component was generated from, as given by `with_origin`.
+    pub fn origin(&self) -> Option<&str> {
+        return self.origin.as_deref();
+    }
+}
+
+impl fmt::Display for SourceMapEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "lines {}-{}: {}",
+            self.lines.start,
+            self.lines.end - 1,
+            self.path_string()
+        )?;
+
+        if let Some(origin) = &self.origin {
+            write!(f, " (generated from {})", origin)?;
+        }
+
+        return Ok(());
+    }
+}
+
+/// Builds a source map while a file is written. The lines are counted as the code is written and
+/// components begin at the next line with any text, so new lines which are trimmed or written
+/// later don't affect the entries.
+#[derive(Debug, Default)]
+pub(crate) struct SourceMapRecorder {
+    lines: usize,
+    last_text_line: usize,
+    open: Vec<OpenEntry>,
+    entries: Vec<SourceMapEntry>,
+}
+
+#[derive(Debug)]
+struct OpenEntry {
+    start: Option<usize>,
+    path: Vec<String>,
+    origin: Option<String>,
+}
+
+impl SourceMapRecorder {
+    pub(crate) fn new() -> Self {
+        return Self::default();
+    }
+
+    /// Counts the lines of some code being written.
+    pub(crate) fn record(&mut self, s: &str) {
+        for (i, part) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.lines += 1;
+            }
+
+            if part.is_empty() {
+                continue;
+            }
+
+            self.last_text_line = self.lines + 1;
+
+            // Only the innermost components can be waiting for their first line.
+            for entry in self.open.iter_mut().rev() {
+                if entry.start.is_some() {
+                    break;
+                }
+
+                entry.start = Some(self.last_text_line);
+            }
+        }
+    }
+
+    fn begin(&mut self, segment: String, origin: Option<&str>) {
+        let mut path = self.open.last().map(|e| e.path.clone()).unwrap_or_default();
+
+        path.push(segment);
+
+        self.open.push(OpenEntry {
+            start: None,
+            path,
+            origin: origin.map(String::from),
+        });
+    }
+
+    fn end(&mut self) {
+        let entry = self.open.pop().expect("a component was begun");
+
+        // A component which didn't write anything has no lines.
+        if let Some(start) = entry.start {
+            self.entries.push(SourceMapEntry {
+                lines: start..self.last_text_line + 1,
+                path: entry.path,
+                origin: entry.origin,
+            });
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> SourceMap {
+        // Sorting by the first line, and then the last, places outer components first.
+        self.entries
+            .sort_by_key(|e| (e.lines.start, std::cmp::Reverse(e.lines.end)));
+
+        return SourceMap {
+            entries: self.entries,
+        };
+    }
+}
+
+/// Marks the code written while it is alive as generated by a component. The entry of the
+/// component is completed when the guard is dropped.
+pub(crate) struct ComponentGuard<'a> {
+    source_map: Option<&'a RefCell<SourceMapRecorder>>,
+}
+
+impl<'a> ComponentGuard<'a> {
+    /// Begins writing a component, writing a comment with its origin if enabled. `segment` is only
+    /// called if a source map is being recorded.
+    pub(crate) fn begin(
+        w: &mut dyn fmt::Write,
+        ctx: &RenderContext<'a>,
+        origin: Option<&str>,
+        segment: impl FnOnce() -> String,
+    ) -> Result<Self, fmt::Error> {
+        let source_map = ctx.source_map();
+
+        if let Some(source_map) = source_map {
+            source_map.borrow_mut().begin(segment(), origin);
+        }
+
+        let guard = Self { source_map };
+
+        if let Some(origin) = origin.filter(|_| ctx.options().origin_comments()) {
+            crate::write_indent(w, ctx.indent_level())?;
+            writeln!(w, "// generated from {}", origin)?;
+        }
+
+        return Ok(guard);
+    }
+}
+
+impl Drop for ComponentGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(source_map) = self.source_map {
+            source_map.borrow_mut().end();
+        }
+    }
+}
+
+/// Writes to `inner`, recording the lines written in a source map.
+pub(crate) struct RecordingWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    source_map: &'a RefCell<SourceMapRecorder>,
+}
+
+impl<'a> RecordingWriter<'a> {
+    pub(crate) fn new(
+        inner: &'a mut dyn fmt::Write,
+        source_map: &'a RefCell<SourceMapRecorder>,
+    ) -> Self {
+        return Self { inner, source_map };
+    }
+}
+
+impl fmt::Write for RecordingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.source_map.borrow_mut().record(s);
+
+        return self.inner.write_str(s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceMapRecorder;
+    use crate::rust_component::{RenderContext, RenderOptions};
+    use crate::{
+        RustFile, RustImplementation, RustMethod, RustModule, RustStruct, RustText, RustTrait,
+        TraitItem,
+    };
+
+    use std::cell::RefCell;
+
+    fn file() -> RustFile {
+        return RustFile::new()
+            .with_import("use std::fmt")
+            .with_component(RustStruct::new("Cow").with_origin("cows.json:1").into())
+            .with_component(
+                RustModule::new("api")
+                    .with_component(
+                        RustImplementation::new("Cow")
+                            .with_component(
+                                RustMethod::new("moo")
+                                    .with_body("todo!()")
+                                    .with_origin("cows.json:7")
+                                    .into(),
+                            )
+                            .into(),
+                    )
+                    .with_component(
+                        RustTrait::new("Animal")
+                            .with_item(TraitItem::new_required(RustMethod::new("speak")))
+                            .into(),
+                    )
+                    .into(),
+            );
+    }
+
+    #[test]
+    fn test_source_map() {
+        let (code, source_map) = file().into_rust_code_with_source_map();
+
+        assert_eq!(code, file().into_rust_code());
+
+        let entries: Vec<String> = source_map.entries().iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                "lines 3-4: struct Cow (generated from cows.json:1)",
+                "lines 6-16: mod api",
+                "lines 7-11: mod api / impl Cow",
+                "lines 8-10: mod api / impl Cow / fn moo (generated from cows.json:7)",
+                "lines 13-15: mod api / trait Animal",
+                "lines 14-14: mod api / trait Animal / fn speak",
+            ]
+        );
+        assert_eq!(
+            source_map.find(9).unwrap().path_string(),
+            "mod api / impl Cow / fn moo"
+        );
+        assert_eq!(source_map.find(12).unwrap().path_string(), "mod api");
+        assert_eq!(source_map.find(1), None);
+    }
+
+    #[test]
+    fn test_empty_component() {
+        let (_, source_map) = RustFile::new()
+            .with_component(RustText::new("").into())
+            .into_rust_code_with_source_map();
+
+        assert!(source_map.entries().is_empty());
+    }
+
+    #[test]
+    fn test_origin_comments() {
+        let file = file().with_render_options(RenderOptions::new().with_origin_comments());
+        let (code, source_map) = file.into_rust_code_with_source_map();
+
+        assert_eq!(
+            code,
+            "use std::fmt;\n\n// generated from cows.json:1\nstruct Cow {\n}\n\nmod api {\n    impl Cow {\n        // generated from cows.json:7\n        fn moo() {\n            todo!()\n        }\n    }\n\n    trait Animal {\n        fn speak();\n    }\n}\n"
+        );
+        assert_eq!(source_map.entries()[0].lines(), 3..6);
+        assert_eq!(source_map.find(9).unwrap().origin(), Some("cows.json:7"));
+    }
+
+    #[test]
+    fn test_origin_is_not_compared() {
+        let generated = RustStruct::new("Cow").with_origin("cows.json:1");
+
+        assert_eq!(generated, RustStruct::new("Cow"));
+        assert_eq!(generated.origin(), Some("cows.json:1"));
+    }
+
+    #[test]
+    fn test_context_compares_source_maps_by_identity() {
+        let a = RefCell::new(SourceMapRecorder::new());
+        let b = RefCell::new(SourceMapRecorder::new());
+        let ctx = RenderContext::new(0, RenderOptions::new());
+
+        assert_eq!(ctx.with_source_map(&a), ctx.with_source_map(&a));
+        assert_ne!(ctx.with_source_map(&a), ctx.with_source_map(&b));
+        assert_ne!(ctx.with_source_map(&a), ctx);
+    }
+}
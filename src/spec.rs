@@ -0,0 +1,780 @@
+//! A declarative description of a file, so that code can be generated from configuration written
+//! in TOML, JSON or YAML instead of Rust.
+//!
+//! Only available with the `spec` feature. A spec contains the following keys, of which only the
+//! ones in bold are required:
+//!
+//! | Key | Contents |
+//! |-----|----------|
+//! | file | `docs`, `imports`, `consts`, `structs`, `enums`, `impls`, `modules` |
+//! | `modules` | **`name`**, `visibility`, `imports`, `consts`, `structs`, `enums`, `impls`, `modules` |
+//! | `consts` | **`name`**, **`type`**, **`value`**, `visibility` |
+//! | `structs` | **`name`**, `visibility`, `docs`, `derives`, `attributes`, `generics`, `fields` |
+//! | `fields` | **`name`**, **`type`**, `visibility`, `docs` |
+//! | `enums` | **`name`**, `visibility`, `docs`, `derives`, `attributes`, `generics`, `variants` |
+//! | `variants` | **`name`** and either `types` for a tuple variant or `fields` for a struct variant |
+//! | `impls` | **`target`**, `trait`, `methods` |
+//! | `methods` | **`name`**, `visibility`, `docs`, `arguments`, `returns`, `body` |
+//!
+//! Visibilities are `pub`, `pub(crate)` or `private`, which is the default. Within a file or
+//! module the consts are generated first, followed by the structs, enums, impls and modules, each
+//! in the order of the spec. Unknown keys are rejected so that typos don't go unnoticed.
+//!
+//! ```
+//! use rmod_gen::{RustFile, SpecFormat};
+//!
+//! let spec = r#"
+//! imports = ["use std::fmt"]
+//!
+//! [[structs]]
+//! name = "Header"
+//! visibility = "pub"
+//! derives = ["Debug", "Clone"]
+//! fields = [{ name = "len", type = "u32", visibility = "pub" }]
+//!
+//! [[enums]]
+//! name = "Opcode"
+//! variants = [{ name = "Nop" }, { name = "Push", types = ["u8"] }]
+//!
+//! [[impls]]
+//! target = "Header"
+//!
+//! [[impls.methods]]
+//! name = "len"
+//! arguments = ["&self"]
+//! returns = "u32"
+//! body = "self.len"
+//! "#;
+//!
+//! let file = RustFile::from_spec(spec, SpecFormat::Toml).unwrap();
+//!
+//! assert_eq!(
+//!     file.into_rust_code(),
+//!     "use std::fmt;\n\n#[derive(Debug, Clone)]\npub struct Header {\n    pub len: u32,\n}\n\nenum Opcode {\n    Nop,\n    Push(u8),\n}\n\nimpl Header {\n    fn len(&self) -> u32 {\n        self.len\n    }\n}\n"
+//! );
+//! ```
+
+use crate::rust_component::{Field, RustComponent, Visibility};
+use crate::{
+    EnumVariant, RustEnum, RustFile, RustImplementation, RustMethod, RustModule, RustStruct,
+    RustVariable,
+};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The languages a spec can be written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SpecFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+/// An error produced when a spec could not be read or doesn't follow the schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecError {
+    message: String,
+    path: Option<PathBuf>,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+/// The root of a spec, which generates a [`RustFile`].
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileSpec {
+    pub docs: String,
+    pub imports: Vec<String>,
+    pub consts: Vec<ConstSpec>,
+    pub structs: Vec<StructSpec>,
+    pub enums: Vec<EnumSpec>,
+    pub impls: Vec<ImplSpec>,
+    pub modules: Vec<ModuleSpec>,
+}
+
+/// Generates a [`RustModule`].
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleSpec {
+    pub name: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub imports: Vec<String>,
+    #[serde(default)]
+    pub consts: Vec<ConstSpec>,
+    #[serde(default)]
+    pub structs: Vec<StructSpec>,
+    #[serde(default)]
+    pub enums: Vec<EnumSpec>,
+    #[serde(default)]
+    pub impls: Vec<ImplSpec>,
+    #[serde(default)]
+    pub modules: Vec<ModuleSpec>,
+}
+
+/// Generates a `const` [`RustVariable`].
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConstSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub tp: String,
+    pub value: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+}
+
+/// Generates a [`RustStruct`].
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructSpec {
+    pub name: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub docs: String,
+    #[serde(default)]
+    pub derives: Vec<String>,
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    #[serde(default)]
+    pub generics: Vec<String>,
+    #[serde(default)]
+    pub fields: Vec<FieldSpec>,
+}
+
+/// Generates a [`Field`] of a struct or struct variant.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub tp: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub docs: String,
+}
+
+/// Generates a [`RustEnum`].
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumSpec {
+    pub name: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub docs: String,
+    #[serde(default)]
+    pub derives: Vec<String>,
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    #[serde(default)]
+    pub generics: Vec<String>,
+    #[serde(default)]
+    pub variants: Vec<VariantSpec>,
+}
+
+/// Generates an [`EnumVariant`], which has at most one of `types` and `fields`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "RawVariantSpec")]
+pub struct VariantSpec {
+    pub name: String,
+    pub types: Vec<String>,
+    pub fields: Vec<FieldSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawVariantSpec {
+    name: String,
+    #[serde(default)]
+    types: Vec<String>,
+    #[serde(default)]
+    fields: Vec<FieldSpec>,
+}
+
+/// Generates a [`RustImplementation`] of `target`, or of `trait` for `target`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImplSpec {
+    pub target: String,
+    #[serde(default, rename = "trait")]
+    pub trait_name: Option<String>,
+    #[serde(default)]
+    pub methods: Vec<MethodSpec>,
+}
+
+/// Generates a [`RustMethod`].
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MethodSpec {
+    pub name: String,
+    #[serde(default, deserialize_with = "visibility")]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub docs: String,
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    #[serde(default)]
+    pub returns: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl SpecFormat {
+    /// The format of a file with the extension `toml`, `json`, `yaml` or `yml`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        return match path.extension()?.to_str()? {
+            "toml" => Some(SpecFormat::Toml),
+            "json" => Some(SpecFormat::Json),
+            "yaml" | "yml" => Some(SpecFormat::Yaml),
+            _ => None,
+        };
+    }
+}
+
+impl SpecError {
+    fn new(message: &str) -> Self {
+        return Self {
+            message: message.to_string(),
+            path: None,
+            line: None,
+            column: None,
+        };
+    }
+
+    /// A description of what went wrong.
+    pub fn message(&self) -> &str {
+        return &self.message;
+    }
+
+    /// The file containing the spec, if it was loaded from one.
+    pub fn path(&self) -> Option<&Path> {
+        return self.path.as_deref();
+    }
+
+    /// The line, starting at 1, at which the error occurred.
+    pub fn line(&self) -> Option<usize> {
+        return self.line;
+    }
+
+    /// The column, starting at 1, at which the error occurred.
+    pub fn column(&self) -> Option<usize> {
+        return self.column;
+    }
+
+    fn located(message: &str, line: usize, column: usize) -> Self {
+        // The format crates append the location to their messages, it is kept separately here.
+        let suffix = format!(" at line {} column {}", line, column);
+
+        return Self {
+            line: Some(line),
+            column: Some(column),
+            ..Self::new(message.strip_suffix(&suffix).unwrap_or(message))
+        };
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, "{}:{}:", line, column)?;
+        }
+
+        if self.path.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl Error for SpecError {}
+
+impl FileSpec {
+    /// Reads a spec written in `format`.
+    pub fn parse(source: &str, format: SpecFormat) -> Result<Self, SpecError> {
+        return deserialize(source, format);
+    }
+
+    /// Reads a spec from a file, in the format given by its extension.
+    pub fn load(path: &Path) -> Result<Self, SpecError> {
+        return read(path);
+    }
+
+    /// The file described by this spec.
+    pub fn to_rust_file(&self) -> RustFile {
+        let mut file = RustFile::new()
+            .with_imports(self.imports.clone())
+            .with_components(components(
+                &self.consts,
+                &self.structs,
+                &self.enums,
+                &self.impls,
+                &self.modules,
+            ));
+
+        if !self.docs.is_empty() {
+            file = file.with_file_docstring(&self.docs);
+        }
+
+        return file;
+    }
+}
+
+impl ModuleSpec {
+    /// The module described by this spec.
+    pub fn to_rust_module(&self) -> RustModule {
+        let mut module = RustModule::new(&self.name)
+            .with_visibility(self.visibility)
+            .with_components(components(
+                &self.consts,
+                &self.structs,
+                &self.enums,
+                &self.impls,
+                &self.modules,
+            ));
+
+        for import in &self.imports {
+            module.push_import(import);
+        }
+
+        return module;
+    }
+}
+
+impl ConstSpec {
+    /// The const described by this spec.
+    pub fn to_rust_variable(&self) -> RustVariable {
+        return RustVariable::new_const(&self.name)
+            .with_type(&self.tp)
+            .with_value(&self.value)
+            .with_visibility(self.visibility);
+    }
+}
+
+impl StructSpec {
+    /// The struct described by this spec.
+    pub fn to_rust_struct(&self) -> RustStruct {
+        let mut rust_struct = RustStruct::new(&self.name)
+            .with_visibility(self.visibility)
+            .with_docs(&self.docs);
+
+        for line in attributes(&self.derives, &self.attributes) {
+            rust_struct = rust_struct.with_cfg(&line);
+        }
+
+        for template in &self.generics {
+            rust_struct.push_template(template);
+        }
+
+        for field in &self.fields {
+            rust_struct.push_field(field.to_field());
+        }
+
+        return rust_struct;
+    }
+}
+
+impl FieldSpec {
+    /// The field described by this spec.
+    pub fn to_field(&self) -> Field {
+        let mut field = Field::new(&self.name, &self.tp, self.visibility);
+
+        if !self.docs.is_empty() {
+            field.set_docs(&self.docs);
+        }
+
+        return field;
+    }
+}
+
+impl EnumSpec {
+    /// The enum described by this spec.
+    pub fn to_rust_enum(&self) -> RustEnum {
+        let mut rust_enum = RustEnum::new(&self.name)
+            .with_visibility(self.visibility)
+            .with_docs(&self.docs);
+
+        for line in attributes(&self.derives, &self.attributes) {
+            rust_enum = rust_enum.with_cfg(&line);
+        }
+
+        for template in &self.generics {
+            rust_enum.push_template(template);
+        }
+
+        for variant in &self.variants {
+            rust_enum.push_variant(variant.to_enum_variant());
+        }
+
+        return rust_enum;
+    }
+}
+
+impl VariantSpec {
+    /// The variant described by this spec.
+    pub fn to_enum_variant(&self) -> EnumVariant {
+        if !self.fields.is_empty() {
+            return EnumVariant::new_struct(
+                &self.name,
+                self.fields.iter().map(FieldSpec::to_field).collect(),
+            );
+        }
+
+        if !self.types.is_empty() {
+            return EnumVariant::new_value(&self.name, self.types.clone());
+        }
+
+        return EnumVariant::new_empty(&self.name);
+    }
+}
+
+impl TryFrom<RawVariantSpec> for VariantSpec {
+    type Error = String;
+
+    fn try_from(raw: RawVariantSpec) -> Result<Self, Self::Error> {
+        if !raw.types.is_empty() && !raw.fields.is_empty() {
+            return Err(format!(
+                "variant `{}` has both `types` and `fields`, a variant is either a tuple or a struct",
+                raw.name
+            ));
+        }
+
+        return Ok(Self {
+            name: raw.name,
+            types: raw.types,
+            fields: raw.fields,
+        });
+    }
+}
+
+impl ImplSpec {
+    /// The implementation described by this spec.
+    pub fn to_rust_implementation(&self) -> RustImplementation {
+        let mut implementation = match &self.trait_name {
+            Some(trait_name) => RustImplementation::new_for(trait_name, &self.target),
+            None => RustImplementation::new(&self.target),
+        };
+
+        for method in &self.methods {
+            implementation.push_component(method.to_rust_method().into());
+        }
+
+        return implementation;
+    }
+}
+
+impl MethodSpec {
+    /// The method described by this spec.
+    pub fn to_rust_method(&self) -> RustMethod {
+        let mut method = RustMethod::new(&self.name)
+            .with_visibility(self.visibility)
+            .with_return_type(&self.returns)
+            .with_body(&self.body);
+
+        for argument in &self.arguments {
+            method.push_argument(argument);
+        }
+
+        if !self.docs.is_empty() {
+            method.set_docs(&self.docs);
+        }
+
+        return method;
+    }
+}
+
+impl RustFile {
+    /// Generates the file described by a spec written in `format`, see [`crate::spec`] for the
+    /// schema.
+    ///
+    /// Only available with the `spec` feature.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, SpecFormat};
+    ///
+    /// let error = RustFile::from_spec("consts:\n  - name: LIMIT\n    typ: usize\n", SpecFormat::Yaml)
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!((error.line(), error.column()), (Some(3), Some(5)));
+    /// assert!(error.message().starts_with("consts[0]: unknown field `typ`"));
+    /// ```
+    pub fn from_spec(source: &str, format: SpecFormat) -> Result<Self, SpecError> {
+        return Ok(FileSpec::parse(source, format)?.to_rust_file());
+    }
+
+    /// Generates the file described by the spec in a file, in the format given by its extension.
+    pub fn load_spec(path: &Path) -> Result<Self, SpecError> {
+        return Ok(FileSpec::load(path)?.to_rust_file());
+    }
+
+    /// Reads a file serialized with the `serde` feature from `format`, e.g. by a generator which
+    /// cached its output or by tooling not written in Rust. Errors are reported as for specs.
+    ///
+    /// ```
+    /// use rmod_gen::{RustFile, RustStruct, SpecFormat};
+    ///
+    /// let file = RustFile::new().with_component(RustStruct::new("Cow").into());
+    /// let json = serde_json::to_string(&file).unwrap();
+    ///
+    /// assert_eq!(RustFile::from_tree(&json, SpecFormat::Json).unwrap(), file);
+    /// ```
+    pub fn from_tree(source: &str, format: SpecFormat) -> Result<Self, SpecError> {
+        return deserialize(source, format);
+    }
+
+    /// Reads a serialized file from a file, in the format given by its extension.
+    pub fn load_tree(path: &Path) -> Result<Self, SpecError> {
+        return read(path);
+    }
+}
+
+/// Deserializes `source`, locating errors in it.
+fn deserialize<T: DeserializeOwned>(source: &str, format: SpecFormat) -> Result<T, SpecError> {
+    return match format {
+        SpecFormat::Toml => toml::from_str(source).map_err(|e| {
+            let message = e.message().to_string();
+
+            match e.span() {
+                Some(span) => {
+                    let before = &source[..span.start];
+                    let line = before.matches('\n').count() + 1;
+                    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+
+                    SpecError::located(&message, line, column)
+                }
+                None => SpecError::new(&message),
+            }
+        }),
+        SpecFormat::Json => serde_json::from_str(source)
+            .map_err(|e| SpecError::located(&e.to_string(), e.line(), e.column())),
+        SpecFormat::Yaml => serde_yaml_ng::from_str(source).map_err(|e| match e.location() {
+            Some(location) => {
+                SpecError::located(&e.to_string(), location.line(), location.column())
+            }
+            None => SpecError::new(&e.to_string()),
+        }),
+    };
+}
+
+/// Deserializes a file in the format given by its extension.
+fn read<T: DeserializeOwned>(path: &Path) -> Result<T, SpecError> {
+    let with_path = |error: SpecError| SpecError {
+        path: Some(path.to_path_buf()),
+        ..error
+    };
+
+    let format = SpecFormat::from_path(path).ok_or_else(|| {
+        with_path(SpecError::new(
+            "unknown spec format, expected a .toml, .json, .yaml or .yml file",
+        ))
+    })?;
+    let source =
+        std::fs::read_to_string(path).map_err(|e| with_path(SpecError::new(&e.to_string())))?;
+
+    return deserialize(&source, format).map_err(with_path);
+}
+
+fn components(
+    consts: &[ConstSpec],
+    structs: &[StructSpec],
+    enums: &[EnumSpec],
+    impls: &[ImplSpec],
+    modules: &[ModuleSpec],
+) -> Vec<RustComponent> {
+    let mut components: Vec<RustComponent> = Vec::new();
+
+    components.extend(consts.iter().map(|c| c.to_rust_variable().into()));
+    components.extend(structs.iter().map(|s| s.to_rust_struct().into()));
+    components.extend(enums.iter().map(|e| e.to_rust_enum().into()));
+    components.extend(impls.iter().map(|i| i.to_rust_implementation().into()));
+    components.extend(modules.iter().map(|m| m.to_rust_module().into()));
+
+    return components;
+}
+
+/// The attributes generated before a struct or enum: its derives and other attributes.
+fn attributes(derives: &[String], attributes: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    if !derives.is_empty() {
+        lines.push(format!("#[derive({})]", derives.join(", ")));
+    }
+
+    lines.extend(attributes.iter().cloned());
+
+    return lines;
+}
+
+fn visibility<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Visibility, D::Error> {
+    let visibility = String::deserialize(deserializer)?;
+
+    return match visibility.as_str() {
+        "pub" => Ok(Visibility::Public),
+        "pub(crate)" => Ok(Visibility::CrateVisible),
+        "private" | "" => Ok(Visibility::Private),
+        other => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Str(other),
+            &"`pub`, `pub(crate)` or `private`",
+        )),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[[consts]]
+name = "VERSION"
+type = "u32"
+value = "3"
+visibility = "pub"
+
+[[modules]]
+name = "api"
+visibility = "pub(crate)"
+
+[[modules.enums]]
+name = "Event"
+derives = ["Debug"]
+variants = [
+    { name = "Quit" },
+    { name = "Key", types = ["char"] },
+    { name = "Move", fields = [{ name = "x", type = "i32" }] },
+]
+
+[[modules.impls]]
+target = "Event"
+trait = "Default"
+
+[[modules.impls.methods]]
+name = "default"
+returns = "Self"
+body = "Event::Quit"
+"#;
+
+    #[test]
+    fn test_formats() {
+        let json = r#"{
+            "consts": [{ "name": "VERSION", "type": "u32", "value": "3", "visibility": "pub" }],
+            "modules": [{
+                "name": "api",
+                "visibility": "pub(crate)",
+                "enums": [{
+                    "name": "Event",
+                    "derives": ["Debug"],
+                    "variants": [
+                        { "name": "Quit" },
+                        { "name": "Key", "types": ["char"] },
+                        { "name": "Move", "fields": [{ "name": "x", "type": "i32" }] }
+                    ]
+                }],
+                "impls": [{
+                    "target": "Event",
+                    "trait": "Default",
+                    "methods": [{ "name": "default", "returns": "Self", "body": "Event::Quit" }]
+                }]
+            }]
+        }"#;
+        let yaml = "
+consts:
+  - { name: VERSION, type: u32, value: '3', visibility: pub }
+modules:
+  - name: api
+    visibility: pub(crate)
+    enums:
+      - name: Event
+        derives: [Debug]
+        variants:
+          - name: Quit
+          - { name: Key, types: [char] }
+          - { name: Move, fields: [{ name: x, type: i32 }] }
+    impls:
+      - target: Event
+        trait: Default
+        methods:
+          - { name: default, returns: Self, body: 'Event::Quit' }
+";
+
+        let file = RustFile::from_spec(TOML, SpecFormat::Toml).unwrap();
+
+        assert_eq!(
+            file.to_rust_code(),
+            "pub const VERSION: u32 = 3;\n\npub(crate) mod api {\n    #[derive(Debug)]\n    enum Event {\n        Quit,\n        Key(char),\n        Move {\n            x: i32,\n        },\n    }\n\n    impl Default for Event {\n        fn default() -> Self {\n            Event::Quit\n        }\n    }\n}\n"
+        );
+        assert_eq!(RustFile::from_spec(json, SpecFormat::Json).unwrap(), file);
+        assert_eq!(RustFile::from_spec(yaml, SpecFormat::Yaml).unwrap(), file);
+    }
+
+    #[test]
+    fn test_docs() {
+        let file = RustFile::from_spec(
+            "[[structs]]\nname = \"Cow\"\ndocs = \"Not #[non_exhaustive].\"\nderives = [\"Debug\"]\n",
+            SpecFormat::Toml,
+        )
+        .unwrap();
+
+        let Some(RustComponent::Struct(cow)) = file.find_component("Cow") else {
+            panic!("the struct is generated");
+        };
+
+        assert_eq!(cow.docs(), "Not #[non_exhaustive].");
+        assert_eq!(cow.cfg(), &vec![String::from("#[derive(Debug)]")]);
+        assert_eq!(
+            file.to_rust_code(),
+            "/// Not #[non_exhaustive].\n#[derive(Debug)]\nstruct Cow {\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = RustFile::from_spec(
+            &TOML.replace("visibility = \"pub(crate)\"", "visibility = \"public\""),
+            SpecFormat::Toml,
+        )
+        .unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (Some(10), Some(14)));
+        assert_eq!(
+            error.message(),
+            "invalid value: string \"public\", expected `pub`, `pub(crate)` or `private`"
+        );
+
+        let error = RustFile::from_spec(
+            "enums:\n  - name: E\n    variants:\n      - { name: A, types: [u8], fields: [{ name: x, type: u8 }] }\n",
+            SpecFormat::Yaml,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "4:7: enums[0].variants: variant `A` has both `types` and `fields`, a variant is either a tuple or a struct"
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let error = FileSpec::load(Path::new("spec.txt")).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "spec.txt: unknown spec format, expected a .toml, .json, .yaml or .yml file"
+        );
+        assert!(FileSpec::load(Path::new("missing.toml"))
+            .unwrap_err()
+            .to_string()
+            .starts_with("missing.toml: "));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::rust_component::{RustComponent, Visibility};
+use crate::{EnumVariant, RustEnum, RustImplementation, RustMethod, RustStruct};
+
+/// Options for the builder generated by [`RustStruct::generate_builder_with`].
+///
+/// ```
+/// use rmod_gen::BuilderOptions;
+///
+/// let options = BuilderOptions::new().with_field_default("port", "8080");
+/// ```
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct BuilderOptions {
+    field_defaults: Vec<(String, String)>,
+}
+
+impl BuilderOptions {
+    /// Creates a new instance in which every field is required.
+    pub fn new() -> Self {
+        return Self {
+            field_defaults: Vec::new(),
+        };
+    }
+
+    /// Makes the field optional, `build` uses the given expression when no value has been set.
+    pub fn with_field_default(mut self, field: &str, expression: &str) -> Self {
+        self.push_field_default(field, expression);
+
+        return self;
+    }
+
+    /// Makes the field optional, `build` uses the given expression when no value has been set.
+    pub fn push_field_default(&mut self, field: &str, expression: &str) {
+        self.field_defaults
+            .push((field.to_string(), expression.to_string()));
+    }
+
+    fn field_default(&self, field: &str) -> Option<&str> {
+        return self
+            .field_defaults
+            .iter()
+            .rev()
+            .find(|(name, _)| name == field)
+            .map(|(_, expression)| expression.as_str());
+    }
+}
+
+impl RustStruct {
+    /// Generates a builder for this struct, following the conventions used by this crate. For a
+    /// struct named `Config` the following components are returned in order:
+    ///
+    /// * `ConfigBuilder`, a struct holding an `Option` for every field of `Config`.
+    /// * `ConfigBuilderError`, an enum with a `Missing*` variant for every field without a default.
+    /// * Implementations of `Display` and `Error` for `ConfigBuilderError`.
+    /// * An implementation of `ConfigBuilder` containing `new`, a `with_*` and `set_*` method for
+    ///   every field and `build`, which returns a `Result<Config, ConfigBuilderError>`.
+    ///
+    /// Every field is required, `build` fails if any of them has not been set.
+    ///
+    /// ```
+    /// use rmod_gen::{RustModule, RustStruct};
+    /// use rmod_gen::rust_component::{Field, Visibility};
+    ///
+    /// let config = RustStruct::new("Config")
+    ///     .with_visibility(Visibility::Public)
+    ///     .with_field(Field::private("name", "String"))
+    ///     .with_field(Field::private("port", "u16"));
+    ///
+    /// let mut module = RustModule::new("config").with_component(config.clone().into());
+    ///
+    /// for component in config.generate_builder() {
+    ///     module.push_component(component);
+    /// }
+    /// ```
+    pub fn generate_builder(&self) -> Vec<RustComponent> {
+        return self.generate_builder_with(&BuilderOptions::new());
+    }
+
+    /// Generates a builder like [`RustStruct::generate_builder`] using the given options. Fields
+    /// given a default use that expression when no value has been set.
+    ///
+    /// ```
+    /// use rmod_gen::{BuilderOptions, RustStruct};
+    /// use rmod_gen::rust_component::Field;
+    ///
+    /// let config = RustStruct::new("Config")
+    ///     .with_field(Field::private("name", "String"))
+    ///     .with_field(Field::private("port", "u16"));
+    ///
+    /// let components = config.generate_builder_with(&BuilderOptions::new().with_field_default("port", "8080"));
+    ///
+    /// assert!(components[4].to_rust_string(0).contains("port: self.port.unwrap_or_else(|| 8080),"));
+    /// ```
+    pub fn generate_builder_with(&self, options: &BuilderOptions) -> Vec<RustComponent> {
+        let builder_name = format!("{}Builder", self.name());
+        let error_name = format!("{}Error", builder_name);
+
+        let mut builder = RustStruct::new(&builder_name).with_visibility(self.visibility());
+        let mut error = RustEnum::new(&error_name)
+            .with_visibility(self.visibility())
+            .with_cfg("#[derive(Clone, Copy, Debug, PartialEq, Eq)]");
+
+        for lifetime in self.lifetimes() {
+            builder.push_lifetime(lifetime);
+        }
+
+        for template in self.templates() {
+            builder.push_template(template);
+        }
+
+        let mut implementation = RustImplementation::for_struct(&builder);
+        let mut error_messages = Vec::new();
+        let mut initial_values = Vec::new();
+        let mut built_values = Vec::new();
+
+        for field in self.fields() {
+            let name = field.name();
+            let tp = field.field_type();
+
+            builder.push_field(crate::rust_component::Field::private(
+                name,
+                &format!("Option<{}>", tp),
+            ));
+
+            initial_values.push(format!("{}: None,", name));
+
+            match options.field_default(name) {
+                Some(default) => {
+                    built_values.push(format!(
+                        "{}: self.{}.unwrap_or_else(|| {}),",
+                        name, name, default
+                    ));
+                }
+                None => {
+                    let variant = format!("Missing{}", crate::rust_component::to_pascal_case(name));
+
+                    built_values.push(format!(
+                        "{}: self.{}.ok_or({}::{})?,",
+                        name, name, error_name, variant
+                    ));
+                    error_messages.push(format!(
+                        "{}::{} => write!(f, \"missing required field '{}'\"),",
+                        error_name, variant, name
+                    ));
+                    error.push_variant(EnumVariant::new_empty(&variant));
+                }
+            }
+        }
+
+        implementation.push_component(
+            RustMethod::new("new")
+                .with_visibility(Visibility::Public)
+                .with_return_type("Self")
+                .with_body(&block("return Self {", &initial_values, "};"))
+                .into(),
+        );
+
+        for field in self.fields() {
+            let name = field.name();
+            let tp = field.field_type();
+
+            implementation.push_component(
+                RustMethod::new(&format!("with_{}", name))
+                    .with_visibility(Visibility::Public)
+                    .with_argument("mut self")
+                    .with_argument(&format!("{}: {}", name, tp))
+                    .with_return_type("Self")
+                    .with_body(&format!("self.set_{}({});\nreturn self;", name, name))
+                    .into(),
+            );
+
+            implementation.push_component(
+                RustMethod::new(&format!("set_{}", name))
+                    .with_visibility(Visibility::Public)
+                    .with_argument("&mut self")
+                    .with_argument(&format!("{}: {}", name, tp))
+                    .with_body(&format!("self.{} = Some({});", name, name))
+                    .into(),
+            );
+        }
+
+        implementation.push_component(
+            RustMethod::new("build")
+                .with_visibility(Visibility::Public)
+                .with_argument("self")
+                .with_return_type(&format!("Result<{}, {}>", self.type_string(), error_name))
+                .with_body(&block(
+                    &format!("return Ok({} {{", self.name()),
+                    &built_values,
+                    "});",
+                ))
+                .into(),
+        );
+
+        let display = RustImplementation::new_for("std::fmt::Display", &error_name).with_component(
+            RustMethod::new("fmt")
+                .with_argument("&self")
+                .with_argument("f: &mut std::fmt::Formatter<'_>")
+                .with_return_type("std::fmt::Result")
+                .with_body(&block("return match *self {", &error_messages, "};"))
+                .into(),
+        );
+
+        return vec![
+            builder.into(),
+            error.into(),
+            display.into(),
+            RustImplementation::new_for("std::error::Error", &error_name).into(),
+            implementation.into(),
+        ];
+    }
+}
+
+/// Creates a block of code, such as a struct expression, with each line indented by one level
+/// between the opening and closing lines.
+pub(crate) fn block(opening: &str, lines: &[String], closing: &str) -> String {
+    let indent_string = crate::indent_string(1);
+    let mut res = opening.to_string();
+
+    for line in lines {
+        res.push_str(&format!("\n{}{}", indent_string, line));
+    }
+
+    if !lines.is_empty() {
+        res.push('\n');
+    }
+
+    res.push_str(closing);
+
+    return res;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Field;
+
+    fn render(components: &[RustComponent]) -> Vec<String> {
+        return components.iter().map(|c| c.to_rust_string(0)).collect();
+    }
+
+    #[test]
+    fn test_builder_struct_and_error() {
+        let s = RustStruct::new("Config")
+            .with_visibility(Visibility::Public)
+            .with_template("T")
+            .with_field(Field::private("name", "T"))
+            .with_field(Field::private("max_size", "usize"))
+            .with_field(Field::private("port", "u16"));
+
+        let rendered = render(
+            &s.generate_builder_with(&BuilderOptions::new().with_field_default("port", "8080")),
+        );
+
+        assert_eq!(rendered.len(), 5);
+        assert_eq!(
+            rendered[0],
+            "pub struct ConfigBuilder<T> {\n    name: Option<T>,\n    max_size: Option<usize>,\n    port: Option<u16>,\n}\n"
+        );
+        assert_eq!(
+            rendered[1],
+            "#[derive(Clone, Copy, Debug, PartialEq, Eq)]\npub enum ConfigBuilderError {\n    MissingName,\n    MissingMaxSize,\n}\n"
+        );
+        assert_eq!(
+            rendered[3],
+            "impl std::error::Error for ConfigBuilderError {\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_builder_methods() {
+        let s = RustStruct::new("Config")
+            .with_field(Field::private("name", "String"))
+            .with_field(Field::private("port", "u16"));
+
+        let rendered = render(
+            &s.generate_builder_with(&BuilderOptions::new().with_field_default("port", "8080")),
+        );
+
+        assert!(rendered[4].starts_with(
+            "impl ConfigBuilder {\n    pub fn new() -> Self {\n        return Self {\n            name: None,\n            port: None,\n        };\n    }\n"
+        ));
+        assert!(rendered[4].contains(
+            "    pub fn with_port(mut self, port: u16) -> Self {\n        self.set_port(port);\n        return self;\n    }\n"
+        ));
+        assert!(rendered[4].contains(
+            "    pub fn set_name(&mut self, name: String) {\n        self.name = Some(name);\n    }\n"
+        ));
+        assert!(rendered[4].contains(
+            "    pub fn build(self) -> Result<Config, ConfigBuilderError> {\n        return Ok(Config {\n            name: self.name.ok_or(ConfigBuilderError::MissingName)?,\n            port: self.port.unwrap_or_else(|| 8080),\n        });\n    }\n"
+        ));
+    }
+}
@@ -0,0 +1,444 @@
+//! Support for checking that generated code compiles, e.g. in the tests of a code generator.
+//!
+//! Only available with the `testing` feature.
+//!
+//! ```
+//! use rmod_gen::testing::CompileCheck;
+//! use rmod_gen::{RustFile, RustMethod};
+//!
+//! let file = RustFile::new()
+//!     .with_component(RustMethod::new("answer").with_return_type("u8").with_body("return \"42\";").into());
+//!
+//! let outcome = CompileCheck::rustc().check(&file).unwrap();
+//! let error = outcome.errors().next().unwrap();
+//!
+//! assert_eq!(error.code(), Some("E0308"));
+//! assert_eq!(error.line(), Some(2));
+//! assert_eq!(error.source().unwrap().path_string(), "fn answer");
+//! ```
+
+use crate::{RustFile, SourceMapEntry};
+
+use serde_json::Value;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// The tool used to check the generated code.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum CheckTool {
+    /// Runs `rustc --emit=metadata` on the file as the root of a library crate.
+    Rustc,
+    /// Runs `cargo check --offline` on a library crate with the file as its root, which allows
+    /// the code to use dependencies.
+    Cargo,
+}
+
+/// Checks that a [`RustFile`] compiles by writing it into a temporary crate and running the
+/// compiler on it.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CompileCheck {
+    tool: CheckTool,
+    binary: String,
+    edition: String,
+    dependencies: Vec<String>,
+}
+
+/// The reasons why the compiler could not be run.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum CheckError {
+    /// The temporary crate could not be created or the compiler could not be run.
+    Io { message: String },
+    /// The compiler failed without reporting any diagnostics, e.g. because a dependency could
+    /// not be resolved offline.
+    Compiler { status: Option<i32>, stderr: String },
+}
+
+/// The result of checking generated code.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CheckOutcome {
+    success: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A message reported by the compiler, e.g. an error or a warning.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Diagnostic {
+    level: String,
+    message: String,
+    code: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    rendered: String,
+    source: Option<SourceMapEntry>,
+}
+
+impl CompileCheck {
+    /// Creates a check which runs the `rustc` found on the `PATH` with the 2021 edition.
+    pub fn rustc() -> Self {
+        return Self {
+            tool: CheckTool::Rustc,
+            binary: String::from("rustc"),
+            edition: String::from("2021"),
+            dependencies: Vec::new(),
+        };
+    }
+
+    /// Creates a check which runs the `cargo` found on the `PATH` with the 2021 edition.
+    pub fn cargo() -> Self {
+        return Self {
+            tool: CheckTool::Cargo,
+            binary: String::from("cargo"),
+            edition: String::from("2021"),
+            dependencies: Vec::new(),
+        };
+    }
+
+    /// Specifies the edition the code is checked with, e.g. `"2018"`.
+    pub fn with_edition(mut self, edition: &str) -> Self {
+        self.edition = edition.to_string();
+
+        return self;
+    }
+
+    /// Specifies the path of the `rustc` or `cargo` executable.
+    pub fn with_binary(mut self, binary: &str) -> Self {
+        self.binary = binary.to_string();
+
+        return self;
+    }
+
+    /// Adds a dependency, as a line of the `[dependencies]` section of a manifest, e.g.
+    /// `serde = "1"`. Dependencies are only used by [`CheckTool::Cargo`] and must be available
+    /// offline.
+    pub fn with_dependency(mut self, dependency: &str) -> Self {
+        self.dependencies.push(dependency.to_string());
+
+        return self;
+    }
+
+    /// Checks the code of `file`. Diagnostics are mapped back to the component which generated
+    /// the line they refer to.
+    pub fn check(&self, file: &RustFile) -> Result<CheckOutcome, CheckError> {
+        let directory = tempfile::tempdir().map_err(io_error)?;
+        let source = directory.path().join("src");
+        let (code, source_map) = file.clone().into_rust_code_with_source_map();
+
+        fs::create_dir(&source).map_err(io_error)?;
+        fs::write(source.join("lib.rs"), code).map_err(io_error)?;
+
+        let output = match self.tool {
+            CheckTool::Rustc => self.run_rustc(directory.path())?,
+            CheckTool::Cargo => self.run_cargo(directory.path())?,
+        };
+
+        let mut diagnostics = match self.tool {
+            CheckTool::Rustc => parse_messages(&output.stderr, Some),
+            CheckTool::Cargo => parse_messages(&output.stdout, |message| {
+                if message["reason"] != "compiler-message" {
+                    return None;
+                }
+
+                // Only the messages of the checked crate are reported, not of its dependencies.
+                let manifest = message["manifest_path"].as_str()?;
+
+                if Path::new(manifest) != directory.path().join("Cargo.toml") {
+                    return None;
+                }
+
+                Some(message["message"].clone())
+            }),
+        };
+
+        if !output.status.success() && !diagnostics.iter().any(|d| d.is_error()) {
+            return Err(CheckError::Compiler {
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+
+        for diagnostic in &mut diagnostics {
+            diagnostic.source = diagnostic
+                .line
+                .and_then(|line| source_map.find(line))
+                .cloned();
+        }
+
+        return Ok(CheckOutcome {
+            success: output.status.success(),
+            diagnostics,
+        });
+    }
+
+    fn run_rustc(&self, directory: &Path) -> Result<Output, CheckError> {
+        return Command::new(&self.binary)
+            .current_dir(directory)
+            .arg("--edition")
+            .arg(&self.edition)
+            .arg("--crate-type")
+            .arg("lib")
+            .arg("--crate-name")
+            .arg("rmod_gen_check")
+            .arg("--emit=metadata")
+            .arg("--error-format=json")
+            .arg("--out-dir")
+            .arg("target")
+            .arg(Path::new("src").join("lib.rs"))
+            .output()
+            .map_err(io_error);
+    }
+
+    fn run_cargo(&self, directory: &Path) -> Result<Output, CheckError> {
+        let manifest = format!(
+            "[package]\nname = \"rmod_gen_check\"\nversion = \"0.0.0\"\nedition = \"{}\"\n\n[dependencies]\n{}\n\n[workspace]\n",
+            self.edition,
+            self.dependencies.join("\n")
+        );
+
+        fs::write(directory.join("Cargo.toml"), manifest).map_err(io_error)?;
+
+        return Command::new(&self.binary)
+            .current_dir(directory)
+            .arg("check")
+            .arg("--offline")
+            .arg("--quiet")
+            .arg("--message-format=json")
+            .arg("--target-dir")
+            .arg("target")
+            .output()
+            .map_err(io_error);
+    }
+}
+
+/// Parses the JSON diagnostics written one per line by the compiler, `extract` selects the
+/// diagnostic from each line.
+fn parse_messages(output: &[u8], extract: impl Fn(Value) -> Option<Value>) -> Vec<Diagnostic> {
+    return String::from_utf8_lossy(output)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(extract)
+        .filter_map(|message| Diagnostic::from_json(&message))
+        .collect();
+}
+
+impl CheckOutcome {
+    /// Whether the code compiled, it may still have warnings.
+    pub fn is_success(&self) -> bool {
+        return self.success;
+    }
+
+    /// Every diagnostic reported by the compiler.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        return &self.diagnostics;
+    }
+
+    /// The errors reported by the compiler.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        return self.diagnostics.iter().filter(|d| d.is_error());
+    }
+}
+
+impl Diagnostic {
+    fn from_json(message: &Value) -> Option<Self> {
+        let text = message["message"].as_str()?;
+        let spans = message["spans"].as_array()?;
+
+        // Summaries such as `aborting due to 1 previous error` don't refer to any code.
+        if spans.is_empty() && (text.starts_with("aborting due to") || text.ends_with("emitted")) {
+            return None;
+        }
+
+        let primary = spans.iter().find(|s| s["is_primary"] == true);
+        let position = |key: &str| primary.and_then(|s| s[key].as_u64()).map(|n| n as usize);
+
+        return Some(Self {
+            level: message["level"].as_str().unwrap_or_default().to_string(),
+            message: text.to_string(),
+            code: message["code"]["code"].as_str().map(String::from),
+            line: position("line_start"),
+            column: position("column_start"),
+            rendered: message["rendered"].as_str().unwrap_or(text).to_string(),
+            source: None,
+        });
+    }
+
+    /// The level of the diagnostic, e.g. `"error"` or `"warning"`.
+    pub fn level(&self) -> &str {
+        return &self.level;
+    }
+
+    /// Whether the diagnostic is an error.
+    pub fn is_error(&self) -> bool {
+        return self.level.starts_with("error");
+    }
+
+    /// The message of the diagnostic.
+    pub fn message(&self) -> &str {
+        return &self.message;
+    }
+
+    /// The code of the diagnostic, e.g. `E0308` or `dead_code`.
+    pub fn code(&self) -> Option<&str> {
+        return self.code.as_deref();
+    }
+
+    /// The line, starting at 1, of the generated code the diagnostic refers to.
+    pub fn line(&self) -> Option<usize> {
+        return self.line;
+    }
+
+    /// The column, starting at 1, of the generated code the diagnostic refers to.
+    pub fn column(&self) -> Option<usize> {
+        return self.column;
+    }
+
+    /// The diagnostic as the compiler would print it.
+    pub fn rendered(&self) -> &str {
+        return &self.rendered;
+    }
+
+    /// The innermost component which generated the line the diagnostic refers to.
+    pub fn source(&self) -> Option<&SourceMapEntry> {
+        return self.source.as_ref();
+    }
+}
+
+/// Checks that `file` compiles with `rustc`, panicking with the errors if it doesn't.
+pub fn assert_compiles(file: &RustFile) {
+    let outcome = CompileCheck::rustc()
+        .check(file)
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    if !outcome.is_success() {
+        let errors: Vec<&str> = outcome.errors().map(|e| e.rendered()).collect();
+
+        panic!(
+            "the generated code doesn't compile:\n{}\n{}",
+            errors.join("\n"),
+            file.to_rust_code()
+        );
+    }
+}
+
+fn io_error(error: std::io::Error) -> CheckError {
+    return CheckError::Io {
+        message: error.to_string(),
+    };
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            CheckError::Io { message } => write!(f, "could not run the compiler: {}", message),
+            CheckError::Compiler {
+                status: Some(status),
+                stderr,
+            } => write!(
+                f,
+                "the compiler exited with status {}: {}",
+                status,
+                stderr.trim()
+            ),
+            CheckError::Compiler {
+                status: None,
+                stderr,
+            } => write!(f, "the compiler was terminated: {}", stderr.trim()),
+        };
+    }
+}
+
+impl Error for CheckError {}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(self.rendered.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Field;
+    use crate::{RustImplementation, RustMethod, RustStruct};
+
+    fn file() -> RustFile {
+        return RustFile::new()
+            .with_component(
+                RustStruct::new("Cow")
+                    .with_field(Field::private("age", "u8"))
+                    .into(),
+            )
+            .with_component(
+                RustImplementation::new("Cow")
+                    .with_component(
+                        RustMethod::new("age")
+                            .with_argument("&self")
+                            .with_return_type("u8")
+                            .with_body("return self.name;")
+                            .into(),
+                    )
+                    .into(),
+            );
+    }
+
+    #[test]
+    fn test_rustc_errors_are_mapped_to_components() {
+        let outcome = CompileCheck::rustc().check(&file()).unwrap();
+        let errors: Vec<&Diagnostic> = outcome.errors().collect();
+
+        assert!(!outcome.is_success());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), Some("E0609"));
+        assert_eq!(errors[0].line(), Some(7));
+        assert_eq!(errors[0].column(), Some(21));
+        assert_eq!(
+            errors[0].source().unwrap().path_string(),
+            "impl Cow / fn age"
+        );
+    }
+
+    #[test]
+    fn test_cargo_check() {
+        let outcome = CompileCheck::cargo().check(&file()).unwrap();
+
+        assert!(!outcome.is_success());
+        assert_eq!(
+            outcome
+                .errors()
+                .map(|e| e.source().unwrap().path_string())
+                .collect::<Vec<_>>(),
+            vec!["impl Cow / fn age"]
+        );
+    }
+
+    #[test]
+    fn test_valid_code() {
+        let file = RustFile::new().with_component(
+            RustMethod::new("answer")
+                .with_visibility(crate::rust_component::Visibility::Public)
+                .with_return_type("u8")
+                .with_body("return 42;")
+                .into(),
+        );
+
+        assert_compiles(&file);
+        assert!(CompileCheck::rustc()
+            .check(&file)
+            .unwrap()
+            .diagnostics()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_missing_compiler() {
+        let error = CompileCheck::rustc()
+            .with_binary("rmod_gen_missing_rustc")
+            .check(&file())
+            .unwrap_err();
+
+        assert!(matches!(error, CheckError::Io { .. }));
+    }
+}
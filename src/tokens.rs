@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::rust_component::{RustComponent, RustComponentTrait};
+use crate::{
+    EnumVariant, RustEnum, RustFile, RustImplementation, RustMethod, RustModule, RustStruct,
+    RustText, RustTrait, RustVariable,
+};
+
+impl RustText {
+    /// Creates a new instance containing the code of a token stream, e.g. the output of `quote!`.
+    ///
+    /// Only available with the `tokens` feature.
+    ///
+    /// ```
+    /// use quote::quote;
+    /// use rmod_gen::RustText;
+    /// use rmod_gen::rust_component::RustComponentTrait;
+    ///
+    /// let text = RustText::from_tokens(quote! { type Unit = u8; });
+    ///
+    /// assert_eq!(text.to_rust_string(0), "type Unit = u8 ;");
+    /// ```
+    pub fn from_tokens(tokens: TokenStream) -> Self {
+        return Self::new(&tokens.to_string());
+    }
+}
+
+impl From<TokenStream> for RustText {
+    fn from(tokens: TokenStream) -> Self {
+        return Self::from_tokens(tokens);
+    }
+}
+
+/// Lexes generated code into tokens. Code which can't be lexed, e.g. because of unbalanced
+/// delimiters, is replaced with a `compile_error!` so that the problem is reported where the
+/// tokens are used.
+fn append_code(code: &str, tokens: &mut TokenStream) {
+    match TokenStream::from_str(code) {
+        Ok(code_tokens) => tokens.extend(code_tokens),
+        Err(error) => {
+            let message = format!("rmod_gen generated invalid tokens: {}", error);
+
+            tokens.extend(quote! { compile_error!(#message); });
+        }
+    }
+}
+
+macro_rules! impl_to_tokens {
+    ($($component:ty),*) => {
+        $(
+            impl ToTokens for $component {
+                fn to_tokens(&self, tokens: &mut TokenStream) {
+                    append_code(&self.to_rust_string(0), tokens);
+                }
+            }
+        )*
+    };
+}
+
+impl_to_tokens!(
+    RustComponent,
+    RustModule,
+    RustStruct,
+    RustEnum,
+    EnumVariant,
+    RustMethod,
+    RustImplementation,
+    RustVariable,
+    RustText,
+    RustTrait
+);
+
+impl ToTokens for RustFile {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        append_code(&self.to_rust_code(), tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Field;
+
+    #[test]
+    fn test_struct_tokens() {
+        let s = RustStruct::new("Cow").with_field(Field::private("age", "u64"));
+
+        assert_eq!(
+            s.to_token_stream().to_string(),
+            quote! { struct Cow { age: u64, } }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_component_tokens_in_quote() {
+        let method: RustComponent = RustMethod::new("answer")
+            .with_return_type("u8")
+            .with_body("return 42;")
+            .into();
+
+        assert_eq!(
+            quote! { impl Cow { #method } }.to_string(),
+            quote! { impl Cow { fn answer() -> u8 { return 42; } } }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let tokens = quote! { const MAX: usize = 5; };
+
+        assert_eq!(
+            RustText::from(tokens.clone()).to_token_stream().to_string(),
+            tokens.to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_code() {
+        assert!(RustText::new("fn broken() {")
+            .to_token_stream()
+            .to_string()
+            .starts_with("compile_error !"));
+    }
+}
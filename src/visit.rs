@@ -0,0 +1,386 @@
+//! Traversal of the component tree of a file.
+//!
+//! [`Visit`] walks the components by reference and [`VisitMut`] by mutable reference. Every method
+//! has a default implementation which visits the children of the component, so a visitor only
+//! overrides the components it is interested in. An overriding method calls the matching `walk_*`
+//! function to keep descending into the children.
+//!
+//! ```
+//! use rmod_gen::visit::Visit;
+//! use rmod_gen::{RustFile, RustImplementation, RustMethod, RustModule};
+//!
+//! #[derive(Default)]
+//! struct CountMethods(usize);
+//!
+//! impl<'a> Visit<'a> for CountMethods {
+//!     fn visit_method(&mut self, _method: &'a RustMethod) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let file = RustFile::new()
+//!     .with_component(RustMethod::new("main").into())
+//!     .with_component(
+//!         RustModule::new("api")
+//!             .with_component(
+//!                 RustImplementation::new("Foo")
+//!                     .with_component(RustMethod::new("new").into())
+//!                     .into(),
+//!             )
+//!             .into(),
+//!     );
+//!
+//! let mut counter = CountMethods::default();
+//! counter.visit_file(&file);
+//!
+//! assert_eq!(counter.0, 2);
+//! ```
+
+use crate::rust_component::RustComponent;
+use crate::{
+    EnumVariant, RustEnum, RustFile, RustImplementation, RustMethod, RustModule, RustStruct,
+    RustText, RustTrait, RustVariable, TraitItem,
+};
+
+/// Visits the components of a file by reference.
+pub trait Visit<'a> {
+    fn visit_file(&mut self, file: &'a RustFile) {
+        walk_file(self, file);
+    }
+
+    fn visit_component(&mut self, component: &'a RustComponent) {
+        walk_component(self, component);
+    }
+
+    fn visit_module(&mut self, module: &'a RustModule) {
+        walk_module(self, module);
+    }
+
+    fn visit_struct(&mut self, _rust_struct: &'a RustStruct) {}
+
+    fn visit_enum(&mut self, rust_enum: &'a RustEnum) {
+        walk_enum(self, rust_enum);
+    }
+
+    fn visit_enum_variant(&mut self, _variant: &'a EnumVariant) {}
+
+    fn visit_method(&mut self, _method: &'a RustMethod) {}
+
+    fn visit_implementation(&mut self, implementation: &'a RustImplementation) {
+        walk_implementation(self, implementation);
+    }
+
+    fn visit_trait(&mut self, rust_trait: &'a RustTrait) {
+        walk_trait(self, rust_trait);
+    }
+
+    fn visit_trait_item(&mut self, item: &'a TraitItem) {
+        walk_trait_item(self, item);
+    }
+
+    fn visit_variable(&mut self, _variable: &'a RustVariable) {}
+
+    fn visit_text(&mut self, _text: &'a RustText) {}
+}
+
+/// Visits the components of a file by mutable reference, e.g. to rename items or add attributes.
+///
+/// ```
+/// use rmod_gen::visit::VisitMut;
+/// use rmod_gen::{RustFile, RustModule, RustStruct};
+///
+/// struct AllowDeadCode;
+///
+/// impl VisitMut for AllowDeadCode {
+///     fn visit_struct_mut(&mut self, rust_struct: &mut RustStruct) {
+///         rust_struct.set_cfg("#[allow(dead_code)]");
+///     }
+/// }
+///
+/// let mut file = RustFile::new().with_component(
+///     RustModule::new("api")
+///         .with_component(RustStruct::new("Foo").into())
+///         .into(),
+/// );
+///
+/// AllowDeadCode.visit_file_mut(&mut file);
+///
+/// assert_eq!(
+///     file.to_rust_code(),
+///     "mod api {\n    #[allow(dead_code)]\n    struct Foo {\n    }\n}\n"
+/// );
+/// ```
+pub trait VisitMut {
+    fn visit_file_mut(&mut self, file: &mut RustFile) {
+        walk_file_mut(self, file);
+    }
+
+    fn visit_component_mut(&mut self, component: &mut RustComponent) {
+        walk_component_mut(self, component);
+    }
+
+    fn visit_module_mut(&mut self, module: &mut RustModule) {
+        walk_module_mut(self, module);
+    }
+
+    fn visit_struct_mut(&mut self, _rust_struct: &mut RustStruct) {}
+
+    fn visit_enum_mut(&mut self, rust_enum: &mut RustEnum) {
+        walk_enum_mut(self, rust_enum);
+    }
+
+    fn visit_enum_variant_mut(&mut self, _variant: &mut EnumVariant) {}
+
+    fn visit_method_mut(&mut self, _method: &mut RustMethod) {}
+
+    fn visit_implementation_mut(&mut self, implementation: &mut RustImplementation) {
+        walk_implementation_mut(self, implementation);
+    }
+
+    fn visit_trait_mut(&mut self, rust_trait: &mut RustTrait) {
+        walk_trait_mut(self, rust_trait);
+    }
+
+    fn visit_trait_item_mut(&mut self, item: &mut TraitItem) {
+        walk_trait_item_mut(self, item);
+    }
+
+    fn visit_variable_mut(&mut self, _variable: &mut RustVariable) {}
+
+    fn visit_text_mut(&mut self, _text: &mut RustText) {}
+}
+
+/// Visits the root components of a file.
+pub fn walk_file<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, file: &'a RustFile) {
+    for component in file.components() {
+        visitor.visit_component(component);
+    }
+}
+
+/// Dispatches to the method of the kind of component.
+pub fn walk_component<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, component: &'a RustComponent) {
+    match component {
+        RustComponent::Module(m) => visitor.visit_module(m),
+        RustComponent::Struct(s) => visitor.visit_struct(s),
+        RustComponent::Enum(e) => visitor.visit_enum(e),
+        RustComponent::EnumVariant(v) => visitor.visit_enum_variant(v),
+        RustComponent::Method(m) => visitor.visit_method(m),
+        RustComponent::Implementation(i) => visitor.visit_implementation(i),
+        RustComponent::Variable(v) => visitor.visit_variable(v),
+        RustComponent::Text(t) => visitor.visit_text(t),
+        RustComponent::Trait(t) => visitor.visit_trait(t),
+    }
+}
+
+/// Visits the components of a module.
+pub fn walk_module<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, module: &'a RustModule) {
+    for component in module.components() {
+        visitor.visit_component(component);
+    }
+}
+
+/// Visits the variants of an enum.
+pub fn walk_enum<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, rust_enum: &'a RustEnum) {
+    for variant in rust_enum.variants() {
+        visitor.visit_enum_variant(variant);
+    }
+}
+
+/// Visits the components of an implementation.
+pub fn walk_implementation<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    implementation: &'a RustImplementation,
+) {
+    for component in implementation.components() {
+        visitor.visit_component(component);
+    }
+}
+
+/// Visits the items of a trait.
+pub fn walk_trait<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, rust_trait: &'a RustTrait) {
+    for item in rust_trait.items() {
+        visitor.visit_trait_item(item);
+    }
+}
+
+/// Visits the method or component of a trait item. Associated types and constants have no
+/// children.
+pub fn walk_trait_item<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, item: &'a TraitItem) {
+    match item {
+        TraitItem::RequiredMethod(m) | TraitItem::ProvidedMethod(m) => visitor.visit_method(m),
+        TraitItem::Other(c) => visitor.visit_component(c),
+        TraitItem::AssociatedType { .. } | TraitItem::AssociatedConst { .. } => {}
+    }
+}
+
+/// Visits the root components of a file.
+pub fn walk_file_mut<V: VisitMut + ?Sized>(visitor: &mut V, file: &mut RustFile) {
+    for component in file.components_mut() {
+        visitor.visit_component_mut(component);
+    }
+}
+
+/// Dispatches to the method of the kind of component.
+pub fn walk_component_mut<V: VisitMut + ?Sized>(visitor: &mut V, component: &mut RustComponent) {
+    match component {
+        RustComponent::Module(m) => visitor.visit_module_mut(m),
+        RustComponent::Struct(s) => visitor.visit_struct_mut(s),
+        RustComponent::Enum(e) => visitor.visit_enum_mut(e),
+        RustComponent::EnumVariant(v) => visitor.visit_enum_variant_mut(v),
+        RustComponent::Method(m) => visitor.visit_method_mut(m),
+        RustComponent::Implementation(i) => visitor.visit_implementation_mut(i),
+        RustComponent::Variable(v) => visitor.visit_variable_mut(v),
+        RustComponent::Text(t) => visitor.visit_text_mut(t),
+        RustComponent::Trait(t) => visitor.visit_trait_mut(t),
+    }
+}
+
+/// Visits the components of a module.
+pub fn walk_module_mut<V: VisitMut + ?Sized>(visitor: &mut V, module: &mut RustModule) {
+    for component in module.components_mut() {
+        visitor.visit_component_mut(component);
+    }
+}
+
+/// Visits the variants of an enum.
+pub fn walk_enum_mut<V: VisitMut + ?Sized>(visitor: &mut V, rust_enum: &mut RustEnum) {
+    for variant in rust_enum.variants_mut() {
+        visitor.visit_enum_variant_mut(variant);
+    }
+}
+
+/// Visits the components of an implementation.
+pub fn walk_implementation_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    implementation: &mut RustImplementation,
+) {
+    for component in implementation.components_mut() {
+        visitor.visit_component_mut(component);
+    }
+}
+
+/// Visits the items of a trait.
+pub fn walk_trait_mut<V: VisitMut + ?Sized>(visitor: &mut V, rust_trait: &mut RustTrait) {
+    for item in rust_trait.items_mut() {
+        visitor.visit_trait_item_mut(item);
+    }
+}
+
+/// Visits the method or component of a trait item.
+pub fn walk_trait_item_mut<V: VisitMut + ?Sized>(visitor: &mut V, item: &mut TraitItem) {
+    match item {
+        TraitItem::RequiredMethod(m) | TraitItem::ProvidedMethod(m) => visitor.visit_method_mut(m),
+        TraitItem::Other(c) => visitor.visit_component_mut(c),
+        TraitItem::AssociatedType { .. } | TraitItem::AssociatedConst { .. } => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_component::Visibility;
+
+    fn file() -> RustFile {
+        return RustFile::new()
+            .with_component(
+                RustStruct::new("Cow")
+                    .with_visibility(Visibility::Public)
+                    .into(),
+            )
+            .with_component(
+                RustModule::new("api")
+                    .with_component(
+                        RustEnum::new("Sound")
+                            .with_visibility(Visibility::Public)
+                            .with_variant(EnumVariant::new_empty("Moo"))
+                            .into(),
+                    )
+                    .with_component(
+                        RustImplementation::new("Cow")
+                            .with_component(
+                                RustMethod::new("moo")
+                                    .with_visibility(Visibility::Public)
+                                    .into(),
+                            )
+                            .with_component(RustMethod::new("chew").into())
+                            .into(),
+                    )
+                    .with_component(
+                        RustTrait::new("Animal")
+                            .with_item(TraitItem::new_required(RustMethod::new("speak")))
+                            .into(),
+                    )
+                    .into(),
+            );
+    }
+
+    #[derive(Default)]
+    struct Items(Vec<String>);
+
+    impl<'a> Visit<'a> for Items {
+        fn visit_struct(&mut self, rust_struct: &'a RustStruct) {
+            if rust_struct.visibility() == Visibility::Public {
+                self.0.push(rust_struct.path_segment());
+            }
+        }
+
+        fn visit_enum(&mut self, rust_enum: &'a RustEnum) {
+            self.0.push(rust_enum.path_segment());
+
+            walk_enum(self, rust_enum);
+        }
+
+        fn visit_enum_variant(&mut self, variant: &'a EnumVariant) {
+            self.0.push(variant.name().to_string());
+        }
+
+        fn visit_method(&mut self, method: &'a RustMethod) {
+            self.0.push(method.path_segment());
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let file = file();
+        let mut items = Items::default();
+
+        items.visit_file(&file);
+
+        assert_eq!(
+            items.0,
+            vec![
+                "struct Cow",
+                "enum Sound",
+                "Moo",
+                "fn moo",
+                "fn chew",
+                "fn speak"
+            ]
+        );
+    }
+
+    struct MakePublic;
+
+    impl VisitMut for MakePublic {
+        fn visit_method_mut(&mut self, method: &mut RustMethod) {
+            method.set_visibility(Visibility::Public);
+        }
+
+        // Trait items take the visibility of the trait.
+        fn visit_trait_mut(&mut self, _rust_trait: &mut RustTrait) {}
+    }
+
+    #[test]
+    fn test_visit_mut() {
+        let mut file = file();
+
+        MakePublic.visit_file_mut(&mut file);
+
+        let code = file.to_rust_code();
+
+        assert!(code.contains("pub fn moo()"));
+        assert!(code.contains("pub fn chew()"));
+        assert!(code.contains("    fn speak();"));
+        assert!(!code.contains("pub fn speak();"));
+    }
+}
@@ -0,0 +1,547 @@
+use crate::rust_component::RenderOptions;
+
+/// The header of an item, e.g. `pub fn name<T>(a: T) -> T where T: Debug {`, which is wrapped in
+/// the style of rustfmt when it doesn't fit on a single line. The where clause is wrapped first,
+/// then the arguments and finally the generics.
+pub(crate) struct Header {
+    prefix: String,
+    generics: Vec<String>,
+    arguments: Option<Vec<String>>,
+    suffix: String,
+    extra: String,
+    terminator: String,
+}
+
+#[derive(Copy, Clone)]
+struct Wrapping {
+    where_clause: bool,
+    arguments: bool,
+    generics: bool,
+}
+
+impl Header {
+    /// Creates a header, `terminator` is the text ending the header such as `" {"` or `";"`.
+    pub(crate) fn new(prefix: &str, terminator: &str) -> Self {
+        return Self {
+            prefix: prefix.to_string(),
+            generics: Vec::new(),
+            arguments: None,
+            suffix: String::new(),
+            extra: String::new(),
+            terminator: terminator.to_string(),
+        };
+    }
+
+    pub(crate) fn with_generics(mut self, generics: Vec<String>) -> Self {
+        self.generics = generics;
+
+        return self;
+    }
+
+    pub(crate) fn with_arguments(mut self, arguments: &[String]) -> Self {
+        self.arguments = Some(arguments.to_vec());
+
+        return self;
+    }
+
+    /// Text following the generics and arguments, e.g. a return type or trait bounds.
+    pub(crate) fn with_suffix(mut self, suffix: &str) -> Self {
+        self.suffix = suffix.to_string();
+
+        return self;
+    }
+
+    /// Text inserted before the terminator, which is wrapped if it is a where clause.
+    pub(crate) fn with_extra(mut self, extra: &str) -> Self {
+        self.extra = extra.to_string();
+
+        return self;
+    }
+
+    /// The lines of the header, without any indentation of the header itself.
+    pub(crate) fn lines(&self, indent_level: usize, options: &RenderOptions) -> Vec<String> {
+        let mut wrapping = Wrapping {
+            where_clause: false,
+            arguments: false,
+            generics: false,
+        };
+
+        let mut lines = self.render(wrapping);
+
+        let fits = |lines: &Vec<String>| lines.iter().all(|l| options.fits(indent_level, l));
+
+        if fits(&lines) {
+            return lines;
+        }
+
+        if self.where_predicates().is_some() {
+            wrapping.where_clause = true;
+            lines = self.render(wrapping);
+
+            if fits(&lines) {
+                return lines;
+            }
+        }
+
+        if self.arguments.as_ref().is_some_and(|a| !a.is_empty()) {
+            wrapping.arguments = true;
+            lines = self.render(wrapping);
+
+            if fits(&lines) {
+                return lines;
+            }
+        }
+
+        if !self.generics.is_empty() {
+            wrapping.generics = true;
+            lines = self.render(wrapping);
+        }
+
+        return lines;
+    }
+
+    fn where_predicates(&self) -> Option<Vec<String>> {
+        let predicates = self.extra.strip_prefix("where ")?;
+
+        return Some(split_top_level(predicates));
+    }
+
+    fn render(&self, wrapping: Wrapping) -> Vec<String> {
+        let item_indent = crate::indent_string(1);
+        let mut lines = Vec::new();
+        let mut current = self.prefix.clone();
+
+        if !self.generics.is_empty() {
+            if wrapping.generics {
+                lines.push(format!("{}<", current));
+                lines.extend(
+                    self.generics
+                        .iter()
+                        .map(|g| format!("{}{},", item_indent, g)),
+                );
+                current = String::from(">");
+            } else {
+                current.push_str(&format!("<{}>", self.generics.join(", ")));
+            }
+        }
+
+        if let Some(arguments) = &self.arguments {
+            if wrapping.arguments && !arguments.is_empty() {
+                lines.push(format!("{}(", current));
+                lines.extend(arguments.iter().map(|a| format!("{}{},", item_indent, a)));
+                current = String::from(")");
+            } else {
+                current.push_str(&format!("({})", arguments.join(", ")));
+            }
+        }
+
+        current.push_str(&self.suffix);
+
+        match self.where_predicates() {
+            Some(predicates) if wrapping.where_clause => {
+                lines.push(current);
+                lines.push(String::from("where"));
+
+                let count = predicates.len();
+
+                for (i, predicate) in predicates.iter().enumerate() {
+                    // A declaration without a body ends after the last predicate, e.g. `T: Debug;`.
+                    if i == count - 1 && self.terminator == ";" {
+                        lines.push(format!("{}{};", item_indent, predicate));
+                    } else {
+                        lines.push(format!("{}{},", item_indent, predicate));
+                    }
+                }
+
+                if self.terminator != ";" {
+                    lines.push(self.terminator.trim_start().to_string());
+                }
+            }
+            _ => {
+                if !self.extra.is_empty() {
+                    current.push(' ');
+                    current.push_str(&self.extra);
+                }
+
+                current.push_str(&self.terminator);
+                lines.push(current);
+            }
+        }
+
+        return lines;
+    }
+}
+
+/// Renders `name(a, b)` followed by `terminator`, placing each item on its own line if the single
+/// line doesn't fit. The lines are not indented.
+pub(crate) fn list(
+    name: &str,
+    items: &[String],
+    terminator: &str,
+    indent_level: usize,
+    options: &RenderOptions,
+) -> Vec<String> {
+    let single_line = format!("{}({}){}", name, items.join(", "), terminator);
+
+    if items.is_empty() || options.fits(indent_level, &single_line) {
+        return vec![single_line];
+    }
+
+    let item_indent = crate::indent_string(1);
+    let mut lines = vec![format!("{}(", name)];
+
+    lines.extend(items.iter().map(|i| format!("{}{},", item_indent, i)));
+    lines.push(format!("){}", terminator));
+
+    return lines;
+}
+
+/// Splits a list on the commas which are not nested within brackets, e.g. the predicates of a
+/// where clause. Empty items, such as after a trailing comma, are removed.
+pub(crate) fn split_top_level(s: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut previous = ' ';
+
+    for (_, c, is_code) in code_chars(s) {
+        match c {
+            _ if !is_code => {}
+            '(' | '[' | '{' | '<' => depth += 1,
+            // The `>` of `->` and `=>` does not close a bracket.
+            '>' if previous == '-' || previous == '=' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(current.trim().to_string());
+                current.clear();
+                previous = c;
+                continue;
+            }
+            _ => {}
+        }
+
+        current.push(c);
+        previous = c;
+    }
+
+    items.push(current.trim().to_string());
+    items.retain(|i| !i.is_empty());
+
+    return items;
+}
+
+/// The characters of `s` with their byte index and whether they are code, i.e. not part of a
+/// string or char literal or a comment, so that brackets in e.g. format strings are skipped.
+fn code_chars(s: &str) -> Vec<(usize, char, bool)> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = Vec::with_capacity(chars.len());
+    let mut literal = 0;
+
+    for (i, (index, c)) in s.char_indices().enumerate() {
+        if literal == 0 {
+            literal = literal_len(&chars[i..], i.checked_sub(1).map(|p| chars[p]));
+        }
+
+        result.push((index, c, literal == 0));
+        literal = literal.saturating_sub(1);
+    }
+
+    return result;
+}
+
+/// Whether each line of `text` after the first starts within a multi-line string literal, in
+/// which case indenting it would change the string.
+pub(crate) fn string_continuations(text: &str) -> Vec<bool> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut continuations = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let len = literal_len(&chars[i..], i.checked_sub(1).map(|p| chars[p]));
+
+        if len == 0 {
+            if chars[i] == '\n' {
+                continuations.push(false);
+            }
+
+            i += 1;
+            continue;
+        }
+
+        // Comments are indented like code, only strings and raw strings keep their lines.
+        let is_string = matches!(chars[i], '"' | 'r');
+        let new_lines = chars[i..i + len].iter().filter(|&&c| c == '\n').count();
+
+        continuations.extend(std::iter::repeat_n(is_string, new_lines));
+        i += len;
+    }
+
+    return continuations;
+}
+
+/// The number of characters of the literal or comment `chars` starts with, or 0 if it starts
+/// with code. `previous` is the character before, used to tell raw strings from identifiers.
+fn literal_len(chars: &[char], previous: Option<char>) -> usize {
+    let follows_identifier =
+        previous.is_some_and(|c| c != 'b' && (c.is_alphanumeric() || c == '_'));
+
+    return match chars {
+        ['/', '/', ..] => chars.iter().position(|&c| c == '\n').unwrap_or(chars.len()),
+        ['/', '*', ..] => {
+            let mut depth = 0;
+            let mut i = 0;
+
+            while i + 1 < chars.len() {
+                match (chars[i], chars[i + 1]) {
+                    ('/', '*') => depth += 1,
+                    ('*', '/') => {
+                        depth -= 1;
+
+                        if depth == 0 {
+                            return i + 2;
+                        }
+                    }
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                }
+
+                i += 2;
+            }
+
+            chars.len()
+        }
+        ['"', ..] | ['\'', '\\', ..] => quoted_len(chars),
+        // A char literal rather than a lifetime, e.g. `'{'`.
+        ['\'', _, '\'', ..] => 3,
+        ['r', rest @ ..] if !follows_identifier => {
+            let hashes = rest.iter().take_while(|&&c| c == '#').count();
+
+            if rest.get(hashes) != Some(&'"') {
+                return 0;
+            }
+
+            let body = &rest[hashes + 1..];
+            let closing: Vec<char> = std::iter::once('"').chain(vec!['#'; hashes]).collect();
+            let end = (0..body.len()).find(|&i| body[i..].starts_with(&closing));
+
+            match end {
+                Some(end) => 1 + hashes + 1 + end + 1 + hashes,
+                None => chars.len(),
+            }
+        }
+        _ => 0,
+    };
+}
+
+/// The number of characters of the string or char literal `chars` starts with, including the
+/// quotes, taking escapes into account.
+fn quoted_len(chars: &[char]) -> usize {
+    let quote = chars[0];
+    let mut i = 1;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    return chars.len();
+}
+
+/// Wraps the last struct literal expression in a line of code, e.g. `Self { a: 1, b: 2 }`, with
+/// each field on its own line. Returns `None` if the line doesn't contain a struct literal.
+pub(crate) fn wrap_struct_literal(line: &str) -> Option<Vec<String>> {
+    let code = code_chars(line);
+    let closing = code
+        .iter()
+        .rev()
+        .find(|&&(_, c, is_code)| is_code && c == '}')?
+        .0;
+    let mut depth = 0;
+    let mut opening = None;
+
+    for &(i, c, is_code) in code.iter().rev().skip_while(|&&(i, _, _)| i > closing) {
+        match c {
+            _ if !is_code => {}
+            '}' => depth += 1,
+            '{' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    opening = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let opening = opening?;
+    let before = line[..opening].trim_end();
+
+    // The literal must be of a type, e.g. `Self` or `Shape::Square`, rather than a block.
+    let path = before
+        .rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .next()?;
+    let last_segment = path.rsplit("::").next()?;
+
+    if !last_segment.starts_with(|c: char| c.is_uppercase()) {
+        return None;
+    }
+
+    // It must also be in expression position, since `if LIMIT { a }` or `x == Y { a }` open a
+    // block after an uppercase constant.
+    let preceding = before[..before.len() - path.len()].trim();
+    let is_assignment = preceding.strip_suffix('=').is_some_and(|rest| {
+        !rest.ends_with(['=', '!', '<', '>', '+', '-', '*', '/', '%', '&', '|', '^'])
+    });
+    let in_expression = preceding.is_empty()
+        || preceding.ends_with(['(', '[', ','])
+        || is_assignment
+        || preceding == "return"
+        || preceding.ends_with(" return");
+
+    if !in_expression {
+        return None;
+    }
+
+    let fields = split_top_level(&line[opening + 1..closing]);
+    let is_field = |f: &String| {
+        let name = f.split(':').next().unwrap_or("").trim();
+
+        !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+
+    if fields.is_empty() || !fields.iter().all(is_field) {
+        return None;
+    }
+
+    let line_indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let field_indent = format!("{}{}", line_indent, crate::indent_string(1));
+
+    let mut lines = vec![format!("{} {{", before)];
+    lines.extend(fields.iter().map(|f| format!("{}{},", field_indent, f)));
+    lines.push(format!("{}}}{}", line_indent, &line[closing + 1..]));
+
+    return Some(lines);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(
+            split_top_level("T: Fn(u8, u16) -> u8, U: Into<(u8, u8)>,"),
+            vec!["T: Fn(u8, u16) -> u8", "U: Into<(u8, u8)>"]
+        );
+    }
+
+    #[test]
+    fn test_header_wraps_where_clause_first() {
+        let header = Header::new("fn long_function_name", " {")
+            .with_generics(vec![String::from("T")])
+            .with_arguments(&[String::from("value: T")])
+            .with_extra("where T: Debug, T: Clone");
+
+        assert_eq!(
+            header.lines(0, &RenderOptions::new().with_max_width(40)),
+            vec![
+                "fn long_function_name<T>(value: T)",
+                "where",
+                "    T: Debug,",
+                "    T: Clone,",
+                "{"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_wraps_generics_last() {
+        let header = Header::new("struct Name", " {").with_generics(vec![
+            String::from("'a"),
+            String::from("TypeParameter: Debug"),
+        ]);
+
+        assert_eq!(
+            header.lines(1, &RenderOptions::new().with_max_width(40)),
+            vec![
+                "struct Name<",
+                "    'a,",
+                "    TypeParameter: Debug,",
+                "> {"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_struct_literal() {
+        assert_eq!(
+            wrap_struct_literal("    return Self::Cow { age: self.age.clone(), name };"),
+            Some(vec![
+                String::from("    return Self::Cow {"),
+                String::from("        age: self.age.clone(),"),
+                String::from("        name,"),
+                String::from("    };"),
+            ])
+        );
+        assert_eq!(wrap_struct_literal("if a { b } else { c }"), None);
+        assert_eq!(wrap_struct_literal("match self {"), None);
+        assert_eq!(wrap_struct_literal("if A { B } else { C }"), None);
+        assert_eq!(wrap_struct_literal("while x == LIMIT { X }"), None);
+        assert_eq!(wrap_struct_literal("Some(x) => Shape { x },"), None);
+        assert_eq!(
+            wrap_struct_literal("let shape = Ok(Shape { x });"),
+            Some(vec![
+                String::from("let shape = Ok(Shape {"),
+                String::from("    x,"),
+                String::from("});"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_wrap_struct_literal_skips_literals() {
+        assert_eq!(
+            wrap_struct_literal(
+                r#"return write!(f, "Point {x_coordinate}", x_coordinate = self.x);"#
+            ),
+            None
+        );
+        assert_eq!(
+            wrap_struct_literal(r#"Self { name: "}", open: '{' } // {"#),
+            Some(vec![
+                String::from("Self {"),
+                String::from(r#"    name: "}","#),
+                String::from("    open: '{',"),
+                String::from("} // {"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_string_continuations() {
+        assert_eq!(
+            string_continuations("let a = \"x\n y\";\n/* a\n b */\nlet b = r#\"\n\"#;"),
+            vec![true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_split_top_level_skips_literals() {
+        assert_eq!(
+            split_top_level(r#"a: "(,", b: r"x, \", c: ',', d: &'a str /* , */"#),
+            vec![
+                r#"a: "(,""#,
+                r#"b: r"x, \""#,
+                "c: ','",
+                "d: &'a str /* , */"
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Runs the `rmod_gen` binary on specs written to a temporary directory.
+
+#![cfg(feature = "cli")]
+#![allow(clippy::needless_return)]
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+const SPEC: &str = r#"
+[[structs]]
+name = "Header"
+visibility = "pub"
+fields = [{ name = "len", type = "u32", visibility = "pub" }]
+"#;
+
+const CODE: &str = "pub struct Header {\n    pub len: u32,\n}\n";
+
+fn rmod_gen(dir: &Path, args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_rmod_gen"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+}
+
+#[test]
+fn test_write_and_check() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("header.toml"), SPEC).unwrap();
+
+    assert_eq!(
+        rmod_gen(dir.path(), &["--check", "header.toml"])
+            .status
+            .code(),
+        Some(1)
+    );
+
+    assert!(rmod_gen(dir.path(), &["header.toml"]).status.success());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("header.rs")).unwrap(),
+        CODE
+    );
+    assert!(rmod_gen(dir.path(), &["--check", "header.toml"])
+        .status
+        .success());
+
+    fs::write(dir.path().join("header.rs"), "// edited\n").unwrap();
+    let output = rmod_gen(dir.path(), &["--check", "header.toml"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "header.rs is out of date, regenerate it from header.toml\n"
+    );
+}
+
+#[test]
+fn test_stdout_and_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("header.yaml"),
+        "structs:\n  - name: Header\n    field: []\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("tree.json"),
+        r#"{"components": [{"Text": {"text": "// generated", "origin": "schema.json:3"}}]}"#,
+    )
+    .unwrap();
+
+    let output = rmod_gen(dir.path(), &["--stdout", "--tree", "tree.json"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "// generated\n");
+
+    let output = rmod_gen(
+        dir.path(),
+        &["--stdout", "--tree", "--origin-comments", "tree.json"],
+    );
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "// generated from schema.json:3\n// generated\n"
+    );
+
+    // Specs don't record origins.
+    let output = rmod_gen(
+        dir.path(),
+        &["--stdout", "--origin-comments", "header.yaml"],
+    );
+
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = rmod_gen(dir.path(), &["--stdout", "header.yaml"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("rmod_gen: header.yaml:3:5: structs[0]: unknown field `field`"));
+}
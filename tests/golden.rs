@@ -0,0 +1,83 @@
+use rmod_gen::rust_component::Field;
+use rmod_gen::{
+    RustEnum, RustFile, RustImplementation, RustMethod, RustStruct, RustText, RustVariable,
+};
+
+/// Generates the file with every API and checks that they agree with each other and with the
+/// golden file.
+fn assert_golden(file: RustFile, golden: &str) {
+    let mut written = Vec::new();
+    file.write_to(&mut written).unwrap();
+
+    let mut streamed = String::new();
+    file.write_rust_code(&mut streamed).unwrap();
+
+    assert_eq!(file.to_rust_code(), golden);
+    assert_eq!(streamed, golden);
+    assert_eq!(String::from_utf8(written).unwrap(), golden);
+    assert_eq!(file.into_rust_code(), golden);
+}
+
+#[test]
+fn test_empty_file() {
+    assert_golden(RustFile::new(), include_str!("golden/empty.rs"));
+}
+
+#[test]
+fn test_imports_only() {
+    assert_golden(
+        RustFile::new()
+            .with_import("use std::fmt")
+            .with_import("use std::io"),
+        include_str!("golden/imports_only.rs"),
+    );
+}
+
+#[test]
+fn test_components_only() {
+    assert_golden(
+        RustFile::new()
+            .with_component(
+                RustVariable::new_const("MAX")
+                    .with_type("u8")
+                    .with_value("5")
+                    .into(),
+            )
+            .with_component(RustText::new("type Unit = u8;\n\n").into())
+            .with_component(RustEnum::new("Empty").into()),
+        include_str!("golden/components_only.rs"),
+    );
+}
+
+#[test]
+fn test_full_file() {
+    let file = RustFile::new()
+        .with_file_docstring("Generated code.\nDo not edit.\n")
+        .with_import("use std::fmt")
+        .with_top_string("type Age = u64;\n")
+        .with_component(
+            RustStruct::new("Cow")
+                .with_field(Field::private("age", "u64"))
+                .into(),
+        )
+        .with_component(
+            RustImplementation::new("Cow")
+                .with_component(
+                    RustMethod::new("age")
+                        .with_argument("&self")
+                        .with_return_type("u64")
+                        .with_body("return self.age;")
+                        .into(),
+                )
+                .into(),
+        )
+        .with_component(
+            RustVariable::new_static("COWS")
+                .with_type("u8")
+                .with_value("3")
+                .into(),
+        )
+        .with_bottom_string("// The end.");
+
+    assert_golden(file, include_str!("golden/full_file.rs"));
+}
@@ -0,0 +1,6 @@
+const MAX: u8 = 5;
+
+type Unit = u8;
+
+enum Empty {
+}
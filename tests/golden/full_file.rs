@@ -0,0 +1,20 @@
+//! Generated code.
+//! Do not edit.
+
+use std::fmt;
+
+type Age = u64;
+
+struct Cow {
+    age: u64,
+}
+
+impl Cow {
+    fn age(&self) -> u64 {
+        return self.age;
+    }
+}
+
+static COWS: u8 = 3;
+
+// The end.
@@ -0,0 +1,2 @@
+use std::fmt;
+use std::io;
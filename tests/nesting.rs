@@ -0,0 +1,239 @@
+//! Property tests checking that components render the same way at any depth, relative to the
+//! component they are nested in.
+
+#![allow(clippy::needless_return)]
+
+use rmod_gen::rust_component::{Field, RustComponent, Visibility};
+use rmod_gen::{
+    EnumVariant, RustEnum, RustImplementation, RustMethod, RustModule, RustStruct, RustText,
+    RustTrait, RustVariable, TraitItem,
+};
+
+const CASES: u64 = 200;
+const MAX_DEPTH: usize = 3;
+
+/// A small xorshift generator so that the cases are random but reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        return self.0;
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        return self.next() % n;
+    }
+
+    fn chance(&mut self) -> bool {
+        return self.below(2) == 0;
+    }
+
+    fn name(&mut self, prefix: &str) -> String {
+        return format!("{}{}", prefix, self.below(100));
+    }
+}
+
+fn visibility(rng: &mut Rng) -> Visibility {
+    return match rng.below(3) {
+        0 => Visibility::Private,
+        1 => Visibility::Public,
+        _ => Visibility::CrateVisible,
+    };
+}
+
+fn method(rng: &mut Rng) -> RustMethod {
+    let mut method = RustMethod::new(&rng.name("method"))
+        .with_visibility(visibility(rng))
+        .with_argument("&self")
+        .with_return_type("u64");
+
+    if rng.chance() {
+        method = method.with_docs("Does something.\nAnd something else.");
+    }
+
+    if rng.chance() {
+        method = method.with_cfg("#[inline]\n#[must_use]");
+    }
+
+    return match rng.below(3) {
+        0 => method.with_body("let value = 1;\nreturn value;"),
+        1 => method.with_body("if self.ready() {\n    return 1;\n}\nreturn 0;"),
+        _ => method.with_body("return 2;"),
+    };
+}
+
+fn rust_struct(rng: &mut Rng) -> RustStruct {
+    let mut s = RustStruct::new(&rng.name("Struct")).with_visibility(visibility(rng));
+
+    if rng.chance() {
+        s = s.with_cfg("#[derive(Debug)]");
+    }
+
+    if rng.chance() {
+        s = s.with_template("T").with_extra("where T: Clone");
+    }
+
+    for _ in 0..rng.below(3) {
+        s = s.with_field(Field::new(&rng.name("field"), "u64", visibility(rng)));
+    }
+
+    return s;
+}
+
+fn rust_enum(rng: &mut Rng) -> RustEnum {
+    let mut e = RustEnum::new(&rng.name("Enum")).with_visibility(visibility(rng));
+
+    for _ in 0..rng.below(4) {
+        e = e.with_variant(match rng.below(3) {
+            0 => EnumVariant::new_empty(&rng.name("Empty")),
+            1 => EnumVariant::new_value(&rng.name("Value"), vec![String::from("u8")]),
+            _ => EnumVariant::new_struct(
+                &rng.name("Struct"),
+                vec![Field::private(&rng.name("field"), "u8")],
+            ),
+        });
+    }
+
+    return e;
+}
+
+fn implementation(rng: &mut Rng) -> RustImplementation {
+    let mut implementation = RustImplementation::new(&rng.name("Struct"));
+
+    for _ in 0..rng.below(4) {
+        implementation = implementation.with_component(match rng.below(3) {
+            0 => RustVariable::new_const(&rng.name("CONST"))
+                .with_type("u8")
+                .with_value("1")
+                .into(),
+            _ => method(rng).into(),
+        });
+    }
+
+    return implementation;
+}
+
+fn rust_trait(rng: &mut Rng) -> RustTrait {
+    let mut t = RustTrait::new(&rng.name("Trait")).with_visibility(visibility(rng));
+
+    for _ in 0..rng.below(4) {
+        t = t.with_item(match rng.below(3) {
+            0 => TraitItem::new_type(&rng.name("Type"), vec![String::from("Copy")], None),
+            1 => TraitItem::new_required(method(rng).with_visibility(Visibility::Private)),
+            _ => TraitItem::new_provided(method(rng).with_visibility(Visibility::Private)),
+        });
+    }
+
+    return t;
+}
+
+fn module(rng: &mut Rng, depth: usize) -> RustModule {
+    let mut module = RustModule::new(&rng.name("module")).with_visibility(visibility(rng));
+
+    if rng.chance() {
+        module = module.with_cfg("#[cfg(test)]");
+    }
+
+    if rng.chance() {
+        module = module.with_import("use super::*");
+    }
+
+    for _ in 0..rng.below(3) {
+        module = module.with_component(component(rng, depth + 1));
+    }
+
+    return module;
+}
+
+fn component(rng: &mut Rng, depth: usize) -> RustComponent {
+    let kinds = if depth < 2 { 8 } else { 7 };
+
+    return match rng.below(kinds) {
+        0 => method(rng).into(),
+        1 => rust_struct(rng).into(),
+        2 => rust_enum(rng).into(),
+        3 => implementation(rng).into(),
+        4 => rust_trait(rng).into(),
+        5 => RustVariable::new_static(&rng.name("STATIC"))
+            .with_type("u8")
+            .with_value("0")
+            .into(),
+        6 => RustText::new("macro_rules! m {\n    () => {};\n}").into(),
+        _ => module(rng, depth).into(),
+    };
+}
+
+/// Nests a component in `depth` modules.
+#[cfg(feature = "parse")]
+fn nest(component: RustComponent, depth: usize) -> RustComponent {
+    let mut nested = component;
+
+    for i in 0..depth {
+        nested = RustModule::new(&format!("outer{}", i))
+            .with_component(nested)
+            .into();
+    }
+
+    return nested;
+}
+
+#[cfg(not(feature = "indent_tabs"))]
+fn indented(code: &str, depth: usize) -> String {
+    return code
+        .split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", "    ".repeat(depth), line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+}
+
+#[cfg(not(feature = "indent_tabs"))]
+#[test]
+fn test_nested_components_are_indented_relative_to_their_parent() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..CASES {
+        let component = component(&mut rng, 0);
+        let code = component.to_rust_string(0);
+
+        for depth in 1..=MAX_DEPTH {
+            assert_eq!(
+                component.to_rust_string(depth),
+                indented(&code, depth),
+                "rendering at depth {} differs from\n{}",
+                depth,
+                code
+            );
+        }
+    }
+}
+
+#[cfg(feature = "parse")]
+#[test]
+fn test_nested_components_reparse_identically() {
+    use rmod_gen::RustFile;
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for _ in 0..CASES {
+        let component = component(&mut rng, 0);
+
+        for depth in 0..=MAX_DEPTH {
+            let code = RustFile::new()
+                .with_component(nest(component.clone(), depth))
+                .into_rust_code();
+            let reparsed = RustFile::parse(&code).unwrap().into_rust_code();
+
+            assert_eq!(reparsed, code, "depth {}", depth);
+        }
+    }
+}
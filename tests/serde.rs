@@ -0,0 +1,114 @@
+//! Round trips of component trees through JSON, as done when caching generator outputs.
+
+#![cfg(feature = "serde")]
+#![allow(clippy::needless_return)]
+
+use rmod_gen::rust_component::{Field, RustComponent, Visibility};
+use rmod_gen::{
+    EnumVariant, RustEnum, RustFile, RustImplementation, RustMethod, RustModule, RustStruct,
+    RustText, RustTrait, RustVariable, TraitItem,
+};
+
+fn file() -> RustFile {
+    return RustFile::new()
+        .with_file_docstring("Generated file")
+        .with_import("use std::fmt")
+        .with_component(
+            RustModule::new("api")
+                .with_visibility(Visibility::Public)
+                .with_component(
+                    RustStruct::new("Header")
+                        .with_visibility(Visibility::Public)
+                        .with_field(Field::new("len", "u32", Visibility::Public))
+                        .with_field(Field::private("id", "u64").with_docs("The id."))
+                        .into(),
+                )
+                .with_component(
+                    RustEnum::new("Opcode")
+                        .with_variant(EnumVariant::new_empty("Nop"))
+                        .with_variant(EnumVariant::new_value("Push", vec![String::from("u8")]))
+                        .into(),
+                )
+                .with_component(
+                    RustImplementation::new("Header")
+                        .with_component(
+                            RustMethod::new("len")
+                                .with_argument("&self")
+                                .with_return_type("u32")
+                                .with_body("self.len")
+                                .into(),
+                        )
+                        .into(),
+                )
+                .with_component(
+                    RustTrait::new("Encode")
+                        .with_item(TraitItem::new_required(
+                            RustMethod::new("encode").with_argument("&self"),
+                        ))
+                        .into(),
+                )
+                .into(),
+        )
+        .with_component(
+            RustVariable::new_const("VERSION")
+                .with_type("u32")
+                .with_value("3")
+                .into(),
+        )
+        .with_component(RustText::new("// end").into());
+}
+
+#[test]
+fn test_json_round_trip() {
+    let file = file();
+    let json = serde_json::to_string(&file).unwrap();
+    let decoded: RustFile = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(decoded, file);
+    assert_eq!(decoded.to_rust_code(), file.to_rust_code());
+}
+
+#[test]
+fn test_json_shape() {
+    let value =
+        serde_json::to_value(RustFile::new().with_component(RustText::new("// a").into())).unwrap();
+
+    assert_eq!(value["components"][0]["Text"]["text"], "// a");
+
+    let component: RustComponent = serde_json::from_str(
+        r#"{"Variable": {
+            "visibility": "Public",
+            "name": "LIMIT",
+            "value": "10",
+            "type": "usize",
+            "kind": "Const",
+            "is_mut": false,
+            "origin": null
+        }}"#,
+    )
+    .unwrap();
+
+    assert_eq!(component.to_rust_string(0), "pub const LIMIT: usize = 10;");
+}
+
+#[test]
+fn test_json_defaults() {
+    let empty: RustFile = serde_json::from_str(r#"{"components": []}"#).unwrap();
+
+    assert_eq!(empty, RustFile::new());
+
+    let file: RustFile = serde_json::from_str(
+        r#"{"components": [
+            {"Struct": {"name": "Marker"}},
+            {"Struct": {"name": "Header", "fields": [{"name": "len", "type": "u32"}]}},
+            {"Variable": {"name": "LIMIT", "value": "10", "type": "usize", "kind": "Const"}},
+            {"Method": {"name": "reset", "visibility": "Public", "body": "todo!()"}}
+        ]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        file.to_rust_code(),
+        "struct Marker {\n}\n\nstruct Header {\n    len: u32,\n}\n\nconst LIMIT: usize = 10;\n\npub fn reset() {\n    todo!()\n}\n"
+    );
+}
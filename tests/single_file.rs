@@ -14,7 +14,7 @@ fn test_single_file() {
         )
         .with_component(RustModule::new("tests").with_cfg("#[cfg(test)]").into());
 
-    let comp = "use std::fmt;\n\nstruct Logger<T> where T: Write {\n    sink: T,\n}\n\n#[cfg(test)]\nmod tests {\n}\n\n";
+    let comp = "use std::fmt;\n\nstruct Logger<T> where T: Write {\n    sink: T,\n}\n\n#[cfg(test)]\nmod tests {\n}\n";
 
     assert_eq!(comp.to_string(), my_file.into_rust_code());
 }